@@ -0,0 +1,5 @@
+-- 03_add_resource_source.sql
+-- 记录资源来自哪个资源站，历史数据均来自 Mikan
+ALTER TABLE resource ADD COLUMN source TEXT NOT NULL DEFAULT 'mikan';
+
+CREATE INDEX IF NOT EXISTS idx_resource_source ON resource(source);
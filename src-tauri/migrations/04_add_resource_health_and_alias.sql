@@ -0,0 +1,16 @@
+-- 04_add_resource_health_and_alias.sql
+-- 资源健康度：做种/下载人数，仅部分来源（如 Nyaa）提供
+ALTER TABLE resource ADD COLUMN seeders INTEGER;
+ALTER TABLE resource ADD COLUMN leechers INTEGER;
+
+-- 番剧别名：用于按标题匹配英文/罗马音发布的资源
+CREATE TABLE IF NOT EXISTS anime_alias (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    alias TEXT NOT NULL,
+    created_at INTEGER,
+    UNIQUE (mikan_id, alias),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_anime_alias_mikan_id ON anime_alias (mikan_id);
@@ -0,0 +1,3 @@
+-- 05_add_resource_size_bytes.sql
+-- 资源大小（字节），file_size 仅为展示字符串
+ALTER TABLE resource ADD COLUMN size_bytes INTEGER;
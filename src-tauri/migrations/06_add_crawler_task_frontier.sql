@@ -0,0 +1,12 @@
+-- 06_add_crawler_task_frontier.sql
+-- 爬虫任务的抓取边界：待抓取的详情页及完成状态，用于中断后恢复
+CREATE TABLE IF NOT EXISTS crawler_task_frontier (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (task_id, url),
+    FOREIGN KEY (task_id) REFERENCES crawler_task (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawler_task_frontier_task_id ON crawler_task_frontier (task_id, done);
@@ -0,0 +1,16 @@
+-- 07_add_parse_quarantine.sql
+-- 解析结果可疑的页面：保存原始HTML，用于发现站点改版并在修复解析器后重新解析
+CREATE TABLE IF NOT EXISTS parse_quarantine (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER,
+    source TEXT NOT NULL,
+    page_kind TEXT NOT NULL,
+    url TEXT NOT NULL UNIQUE,
+    mikan_id INTEGER,
+    reason TEXT NOT NULL,
+    raw_html TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES crawler_task (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_parse_quarantine_created_at ON parse_quarantine (created_at);
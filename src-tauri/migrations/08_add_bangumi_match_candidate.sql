@@ -0,0 +1,17 @@
+-- 08_add_bangumi_match_candidate.sql
+-- 缺少 bangumi_id 的番剧通过 Bangumi 搜索得到的候选条目，高置信度自动关联，其余由用户确认
+CREATE TABLE IF NOT EXISTS bangumi_match_candidate (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    bangumi_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    name_cn TEXT,
+    air_date TEXT,
+    confidence REAL NOT NULL,
+    status TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE (mikan_id, bangumi_id),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_bangumi_match_candidate_status ON bangumi_match_candidate (status, mikan_id);
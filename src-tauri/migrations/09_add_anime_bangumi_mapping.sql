@@ -0,0 +1,13 @@
+-- 09_add_anime_bangumi_mapping.sql
+-- 用户维护的 Mikan 番剧与 Bangumi 条目对应关系，优先于 anime.bangumi_id。
+-- 一个 Mikan 番剧可以对应多个 Bangumi 条目（如分季），有映射的番剧不再按 anime.bangumi_id 匹配
+CREATE TABLE IF NOT EXISTS anime_bangumi_mapping (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    bangumi_id INTEGER NOT NULL,
+    created_at INTEGER,
+    UNIQUE (mikan_id, bangumi_id),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_anime_bangumi_mapping_bangumi_id ON anime_bangumi_mapping (bangumi_id);
@@ -0,0 +1,25 @@
+-- 10_add_torrent_metadata.sql
+-- 从 resource.torrent_url 下载并解析的种子信息，以及种子内的文件清单
+CREATE TABLE IF NOT EXISTS torrent_metadata (
+    resource_id INTEGER PRIMARY KEY,
+    status TEXT NOT NULL,
+    info_hash TEXT,
+    name TEXT,
+    total_size INTEGER,
+    piece_length INTEGER,
+    file_count INTEGER,
+    error_message TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    fetched_at INTEGER NOT NULL,
+    FOREIGN KEY (resource_id) REFERENCES resource (id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS torrent_file (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    resource_id INTEGER NOT NULL,
+    file_index INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    UNIQUE (resource_id, file_index),
+    FOREIGN KEY (resource_id) REFERENCES resource (id) ON DELETE CASCADE
+);
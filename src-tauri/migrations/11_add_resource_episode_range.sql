@@ -0,0 +1,3 @@
+-- 11_add_resource_episode_range.sql
+-- 合集资源的结束集数，episode_number 为起始集数；单集资源为空
+ALTER TABLE resource ADD COLUMN episode_end INTEGER;
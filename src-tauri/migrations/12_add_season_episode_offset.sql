@@ -0,0 +1,8 @@
+-- 12_add_season_episode_offset.sql
+-- 标题中解析出的季度
+ALTER TABLE resource ADD COLUMN season INTEGER;
+
+-- 映射的集数偏移：Bangumi 集数 = 资源集数 - episode_offset，用于分割放送的绝对集数。
+-- season 非空时只匹配该季度的资源
+ALTER TABLE anime_bangumi_mapping ADD COLUMN season INTEGER;
+ALTER TABLE anime_bangumi_mapping ADD COLUMN episode_offset INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- 13_add_resource_episode_kind.sql
+-- 剧集类型（正片/特别篇/OP/ED/剧场版）与精确集数。
+-- episode_sort 可为小数（如13.5），特别篇等在各自类型内编号；episode_number 只记录正片的整数集数
+ALTER TABLE resource ADD COLUMN episode_kind TEXT NOT NULL DEFAULT 'Main';
+ALTER TABLE resource ADD COLUMN episode_sort REAL;
+
+UPDATE resource SET episode_sort = episode_number WHERE episode_number IS NOT NULL;
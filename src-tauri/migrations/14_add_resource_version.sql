@@ -0,0 +1,7 @@
+-- 14_add_resource_version.sql
+-- 发布版本（v2/v3），未标注时为空，视为 v1。
+-- superseded_by 指向同一字幕组、同一集、同分辨率与字幕类型的最新修订版
+ALTER TABLE resource ADD COLUMN version INTEGER;
+ALTER TABLE resource ADD COLUMN superseded_by INTEGER REFERENCES resource (id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_resource_revision ON resource (mikan_id, subtitle_group_id, episode_sort);
@@ -0,0 +1,28 @@
+-- 15_add_resource_language_model.sql
+-- 结构化字幕信息：字幕语言（逗号分隔的 chs/cht/jpn/eng）、交付方式（内封/内嵌/外挂）与音轨语言。
+-- 旧数据按 subtitle_type 与标题粗略回填，重新解析后以解析器结果为准
+ALTER TABLE resource ADD COLUMN subtitle_languages TEXT;
+ALTER TABLE resource ADD COLUMN subtitle_delivery TEXT;
+ALTER TABLE resource ADD COLUMN audio_language TEXT;
+
+UPDATE resource
+SET subtitle_languages = NULLIF(trim(
+        (CASE WHEN subtitle_type LIKE '%简%' OR subtitle_type LIKE '%CHS%' OR subtitle_type LIKE '%GB%'
+                   OR ((subtitle_type LIKE '%中日%' OR subtitle_type LIKE '%中字%' OR subtitle_type LIKE '%中文字幕%')
+                       AND subtitle_type NOT LIKE '%繁%')
+              THEN 'chs,' ELSE '' END)
+        || (CASE WHEN subtitle_type LIKE '%繁%' OR subtitle_type LIKE '%CHT%' OR subtitle_type LIKE '%BIG5%'
+                 THEN 'cht,' ELSE '' END)
+        || (CASE WHEN subtitle_type LIKE '%日%' AND subtitle_type <> '日语原声' THEN 'jpn,' ELSE '' END)
+        || (CASE WHEN subtitle_type LIKE '%英%' THEN 'eng,' ELSE '' END),
+        ','), ''),
+    subtitle_delivery = CASE
+        WHEN title LIKE '%内封%' OR title LIKE '%内挂%' THEN 'Soft'
+        WHEN title LIKE '%内嵌%' THEN 'Hard'
+        WHEN title LIKE '%外挂%' THEN 'External'
+    END,
+    audio_language = CASE
+        WHEN title LIKE '%日语原声%' THEN 'jpn'
+        WHEN title LIKE '%国语%' OR title LIKE '%国配%' OR title LIKE '%中配%' THEN 'zho'
+        WHEN title LIKE '%粤语%' THEN 'yue'
+    END;
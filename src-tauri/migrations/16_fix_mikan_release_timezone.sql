@@ -0,0 +1,5 @@
+-- 16_fix_mikan_release_timezone.sql
+-- Mikan 页面与RSS中的发布时间为北京时间（UTC+8，无夏令时），此前按UTC存储，修正为真实的UTC时间戳
+UPDATE resource
+SET release_date = release_date - 8 * 3600 * 1000
+WHERE source = 'mikan' AND release_date IS NOT NULL;
@@ -0,0 +1,29 @@
+-- 17_add_resource_technical_attributes.sql
+-- 视频/音频技术参数：编码、位深、HDR、音频编码、片源与流媒体平台、封装格式。
+-- 旧数据只按标题粗略回填编码、位深与封装格式，其余字段重新解析标题后写入
+ALTER TABLE resource ADD COLUMN video_codec TEXT;
+ALTER TABLE resource ADD COLUMN bit_depth INTEGER;
+ALTER TABLE resource ADD COLUMN hdr TEXT;
+ALTER TABLE resource ADD COLUMN audio_codec TEXT;
+ALTER TABLE resource ADD COLUMN release_source TEXT;
+ALTER TABLE resource ADD COLUMN platform TEXT;
+ALTER TABLE resource ADD COLUMN container TEXT;
+
+UPDATE resource
+SET video_codec = CASE
+        WHEN title LIKE '%HEVC%' OR title LIKE '%x265%' OR title LIKE '%H265%' OR title LIKE '%H.265%' THEN 'HEVC'
+        WHEN title LIKE '%AVC%' OR title LIKE '%x264%' OR title LIKE '%H264%' OR title LIKE '%H.264%' THEN 'AVC'
+        WHEN title LIKE '%AV1%' THEN 'AV1'
+    END,
+    bit_depth = CASE
+        WHEN title LIKE '%10bit%' OR title LIKE '%10-bit%' OR title LIKE '%Ma10p%' OR title LIKE '%Hi10p%' THEN 10
+    END,
+    audio_codec = CASE
+        WHEN title LIKE '%FLAC%' THEN 'FLAC'
+        WHEN title LIKE '%AAC%' THEN 'AAC'
+        WHEN title LIKE '%Opus%' THEN 'Opus'
+    END,
+    container = CASE
+        WHEN title LIKE '%MKV%' THEN 'MKV'
+        WHEN title LIKE '%MP4%' THEN 'MP4'
+    END;
@@ -0,0 +1,3 @@
+-- 18_add_resource_parser_version.sql
+-- 记录解析标题所用的解析器版本，解析规则升级后由重新解析任务更新早于当前版本（或为空）的资源
+ALTER TABLE resource ADD COLUMN parser_version INTEGER;
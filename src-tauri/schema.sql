@@ -20,6 +20,49 @@ CREATE TABLE IF NOT EXISTS anime (
 CREATE INDEX IF NOT EXISTS idx_anime_bangumi_id ON anime (bangumi_id);
 CREATE INDEX IF NOT EXISTS idx_anime_title ON anime (title);
 
+-- AnimeAlias表：用于按标题匹配英文/罗马音发布的资源
+CREATE TABLE IF NOT EXISTS anime_alias (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    alias TEXT NOT NULL,
+    created_at INTEGER,
+    UNIQUE (mikan_id, alias),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_anime_alias_mikan_id ON anime_alias (mikan_id);
+
+-- AnimeBangumiMapping表：用户维护的 Mikan 番剧与 Bangumi 条目对应关系，优先于 anime.bangumi_id
+CREATE TABLE IF NOT EXISTS anime_bangumi_mapping (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    bangumi_id INTEGER NOT NULL,
+    created_at INTEGER,
+    season INTEGER, -- 非空时只匹配该季度的资源
+    episode_offset INTEGER NOT NULL DEFAULT 0, -- Bangumi 集数 = 资源集数 - episode_offset
+    UNIQUE (mikan_id, bangumi_id),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_anime_bangumi_mapping_bangumi_id ON anime_bangumi_mapping (bangumi_id);
+
+-- BangumiMatchCandidate表：缺少 bangumi_id 的番剧通过 Bangumi 搜索得到的候选条目
+CREATE TABLE IF NOT EXISTS bangumi_match_candidate (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    mikan_id INTEGER NOT NULL,
+    bangumi_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    name_cn TEXT,
+    air_date TEXT,
+    confidence REAL NOT NULL,
+    status TEXT NOT NULL, -- Rust端为MatchCandidateStatus枚举
+    created_at INTEGER NOT NULL,
+    UNIQUE (mikan_id, bangumi_id),
+    FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_bangumi_match_candidate_status ON bangumi_match_candidate (status, mikan_id);
+
 -- CrawlerTask表
 CREATE TABLE IF NOT EXISTS crawler_task (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -40,6 +83,34 @@ CREATE TABLE IF NOT EXISTS crawler_task (
 
 CREATE INDEX IF NOT EXISTS idx_crawler_task_created_at ON crawler_task (created_at);
 
+-- CrawlerTaskFrontier表：爬虫任务待抓取的详情页及完成状态，用于中断后恢复
+CREATE TABLE IF NOT EXISTS crawler_task_frontier (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER NOT NULL,
+    url TEXT NOT NULL,
+    done INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (task_id, url),
+    FOREIGN KEY (task_id) REFERENCES crawler_task (id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_crawler_task_frontier_task_id ON crawler_task_frontier (task_id, done);
+
+-- ParseQuarantine表：解析结果可疑的页面及其原始HTML
+CREATE TABLE IF NOT EXISTS parse_quarantine (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    task_id INTEGER,
+    source TEXT NOT NULL,
+    page_kind TEXT NOT NULL,
+    url TEXT NOT NULL UNIQUE,
+    mikan_id INTEGER,
+    reason TEXT NOT NULL,
+    raw_html TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    FOREIGN KEY (task_id) REFERENCES crawler_task (id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_parse_quarantine_created_at ON parse_quarantine (created_at);
+
 
 -- SubtitleGroup表
 CREATE TABLE IF NOT EXISTS subtitle_group (
@@ -89,6 +160,27 @@ CREATE TABLE IF NOT EXISTS resource (
     release_date INTEGER,
     created_at INTEGER,
     updated_at INTEGER,
+    source TEXT NOT NULL DEFAULT 'mikan', -- 资源站，如 mikan、nyaa、dmhy
+    seeders INTEGER,
+    leechers INTEGER,
+    size_bytes INTEGER, -- file_size 仅为展示字符串
+    episode_end INTEGER, -- 合集资源的结束集数，episode_number 为起始集数
+    season INTEGER,
+    episode_kind TEXT NOT NULL DEFAULT 'Main', -- Rust端为EpisodeKind枚举
+    episode_sort REAL, -- 可为小数（如13.5），特别篇等在各自类型内编号
+    version INTEGER, -- 未标注时为空，视为 v1
+    superseded_by INTEGER REFERENCES resource (id) ON DELETE SET NULL,
+    subtitle_languages TEXT, -- 逗号分隔的 chs/cht/jpn/eng
+    subtitle_delivery TEXT, -- Rust端为SubtitleDelivery枚举
+    audio_language TEXT,
+    video_codec TEXT,
+    bit_depth INTEGER,
+    hdr TEXT,
+    audio_codec TEXT,
+    release_source TEXT,
+    platform TEXT,
+    container TEXT,
+    parser_version INTEGER,
     FOREIGN KEY (mikan_id) REFERENCES anime (mikan_id),
     FOREIGN KEY (subtitle_group_id) REFERENCES subtitle_group (id)
 );
@@ -100,6 +192,57 @@ CREATE INDEX IF NOT EXISTS idx_resource_title ON resource (title);
 CREATE INDEX IF NOT EXISTS idx_resource_resolution ON resource (resolution);
 CREATE INDEX IF NOT EXISTS idx_resource_subtitle_type ON resource (subtitle_type);
 CREATE INDEX IF NOT EXISTS idx_release_date_desc ON resource (release_date DESC);
+CREATE INDEX IF NOT EXISTS idx_resource_source ON resource (source);
+CREATE INDEX IF NOT EXISTS idx_resource_revision ON resource (mikan_id, subtitle_group_id, episode_sort);
+
+-- TorrentMetadata表：从 torrent_url 下载并解析的种子信息
+CREATE TABLE IF NOT EXISTS torrent_metadata (
+    resource_id INTEGER PRIMARY KEY,
+    status TEXT NOT NULL, -- Rust端为TorrentMetadataStatus枚举
+    info_hash TEXT,
+    name TEXT,
+    total_size INTEGER,
+    piece_length INTEGER,
+    file_count INTEGER,
+    error_message TEXT,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    fetched_at INTEGER NOT NULL,
+    FOREIGN KEY (resource_id) REFERENCES resource (id) ON DELETE CASCADE
+);
+
+-- TorrentFile表：种子内的文件清单
+CREATE TABLE IF NOT EXISTS torrent_file (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    resource_id INTEGER NOT NULL,
+    file_index INTEGER NOT NULL,
+    path TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    UNIQUE (resource_id, file_index),
+    FOREIGN KEY (resource_id) REFERENCES resource (id) ON DELETE CASCADE
+);
+
+-- DownloadTask表
+CREATE TABLE IF NOT EXISTS download_task (
+    id INTEGER PRIMARY KEY,
+    magnet_url TEXT NOT NULL,
+    save_path TEXT,
+    status TEXT NOT NULL, -- Rust端为DownloadStatus枚举
+    title TEXT NOT NULL,
+    bangumi_id INTEGER NOT NULL,
+    resource_id INTEGER NOT NULL,
+    episode_number INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    name_cn TEXT NOT NULL,
+    cover TEXT NOT NULL,
+    total_size INTEGER NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    error_msg TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_task_bangumi_id ON download_task (bangumi_id);
+CREATE INDEX IF NOT EXISTS idx_download_task_resource_id ON download_task (resource_id);
+CREATE INDEX IF NOT EXISTS idx_download_task_episode_number ON download_task (episode_number);
 
 -- Bangumi subject 缓存表
 CREATE TABLE IF NOT EXISTS bangumi_subject_cache (
@@ -1,10 +1,19 @@
-use crate::error::AppError;
+use crate::error::{AppError, DomainError, InputError};
 use crate::{
-    repositories::{anime::AnimeRepository, resource::ResourceRepository},
-    services::bangumi_service::BangumiService,
+    models::{AnimeAlias, AnimeBangumiMapping, BangumiMatchCandidate, EpisodeKind},
+    repositories::{
+        anime::AnimeRepository,
+        anime_alias::AnimeAliasRepository,
+        anime_mapping::AnimeMappingRepository,
+        bangumi_match::BangumiMatchRepository,
+        base::Repository,
+        resource::{ResourceFilter, ResourceRepository},
+    },
+    services::{bangumi_service::BangumiService, torrent_service::TorrentService},
     types::bangumi::{
         BangumiEpisodesData, BangumiSubject, BangumiWeekday, EpisodeAvailabilityData,
-        EpisodeResourcesData, Pagination, SearchLibraryResponse,
+        EpisodeResourcesData, Pagination, ReconcileSummary, ResourceQuery, SearchLibraryResponse,
+        TorrentManifest,
     },
 };
 use sqlx::SqlitePool;
@@ -45,28 +54,38 @@ pub async fn get_episodes(
         .await
 }
 
+/// 集数可用性，episode_type 为 Bangumi 章节类型（缺省为本篇），键为该类型内的集数（可为小数）
 #[command(rename_all = "snake_case")]
 pub async fn get_episode_availability(
     bangumi_id: i64,
+    episode_type: Option<i64>,
     pool: State<'_, Arc<SqlitePool>>,
 ) -> Result<Option<EpisodeAvailabilityData>, AppError> {
+    let kind = episode_kind(episode_type)?;
     let anime_repo = AnimeRepository::new(&pool);
     let resource_repo = ResourceRepository::new(&pool);
 
-    let anime = anime_repo.get_by_bangumi_id(bangumi_id).await?;
+    let mappings = anime_repo
+        .episode_mappings_by_bangumi_id(bangumi_id)
+        .await?;
 
-    if let Some(anime) = anime {
-        let episode_counts = resource_repo.count_by_episode(anime.mikan_id).await?;
+    if !mappings.is_empty() {
+        let episode_counts = resource_repo.count_by_episode(&mappings, kind).await?;
 
-        let mut episodes_map = std::collections::HashMap::new();
+        let mut episodes_map: std::collections::HashMap<
+            String,
+            crate::types::bangumi::EpisodeAvailability,
+        > = std::collections::HashMap::new();
         for count in episode_counts {
-            episodes_map.insert(
-                count.episode_number.to_string(),
-                crate::types::bangumi::EpisodeAvailability {
-                    available: count.resource_count > 0,
-                    resource_count: count.resource_count,
-                },
-            );
+            // 不同番剧的偏移可能换算到同一集，合并计数
+            let entry = episodes_map
+                .entry(format_episode_sort(count.episode_sort))
+                .or_insert(crate::types::bangumi::EpisodeAvailability {
+                    available: false,
+                    resource_count: 0,
+                });
+            entry.resource_count += count.resource_count;
+            entry.available = entry.resource_count > 0;
         }
 
         Ok(Some(EpisodeAvailabilityData {
@@ -78,19 +97,46 @@ pub async fn get_episode_availability(
     }
 }
 
+/// 某一集的资源，默认不含已被同组更高版本（v2/v3）取代的资源
 #[command(rename_all = "snake_case")]
 pub async fn get_episode_resources(
     bangumi_id: i64,
-    episode: i64,
+    episode: f64,
+    episode_type: Option<i64>,
+    include_superseded: Option<bool>,
     pool: State<'_, Arc<SqlitePool>>,
     config: State<'_, crate::config::Config>,
 ) -> Result<Option<EpisodeResourcesData>, AppError> {
+    let filter = ResourceFilter {
+        episode: Some((episode_kind(episode_type)?, episode)),
+        include_superseded: include_superseded.unwrap_or(false),
+        ..Default::default()
+    };
     let service = BangumiService::new(pool.inner().clone(), config.inner().clone());
     service
-        .aggregate_resources(bangumi_id, Some(episode), None, None, None, None)
+        .aggregate_resources(bangumi_id, filter, None, None)
         .await
 }
 
+fn episode_kind(episode_type: Option<i64>) -> Result<EpisodeKind, AppError> {
+    let episode_type = episode_type.unwrap_or(0);
+    EpisodeKind::from_bangumi_type(episode_type).ok_or_else(|| {
+        AppError::Input(InputError::Invalid(format!(
+            "不支持的剧集类型: {}",
+            episode_type
+        )))
+    })
+}
+
+// 整数集数不带小数点，与前端按 sort 取值的键一致
+fn format_episode_sort(sort: f64) -> String {
+    if sort.fract() == 0.0 {
+        format!("{}", sort as i64)
+    } else {
+        format!("{}", sort)
+    }
+}
+
 #[command(rename_all = "snake_case")]
 pub async fn search_library(
     query: String,
@@ -124,18 +170,206 @@ pub async fn search_library(
     })
 }
 
+/// 番剧的全部资源，筛选条件见 ResourceQuery
 #[command(rename_all = "snake_case")]
 pub async fn get_anime_resources(
     bangumi_id: i64,
-    resolution: Option<String>,
-    subtitle_type: Option<String>,
-    limit: Option<i64>,
-    offset: Option<i64>,
+    query: Option<ResourceQuery>,
     pool: State<'_, Arc<SqlitePool>>,
     config: State<'_, crate::config::Config>,
 ) -> Result<Option<EpisodeResourcesData>, AppError> {
+    let query = query.unwrap_or_default();
+    let filter = ResourceFilter {
+        resolution: query.resolution,
+        subtitle_type: query.subtitle_type,
+        subtitle_languages: query.subtitle_languages,
+        subtitle_delivery: query.subtitle_delivery,
+        audio_language: query.audio_language,
+        video_codec: query.video_codec,
+        bit_depth: query.bit_depth,
+        hdr: query.hdr,
+        audio_codec: query.audio_codec,
+        release_source: query.release_source,
+        platform: query.platform,
+        container: query.container,
+        include_superseded: query.include_superseded,
+        ..Default::default()
+    };
+    let service = BangumiService::new(pool.inner().clone(), config.inner().clone());
+    service
+        .aggregate_resources(bangumi_id, filter, query.limit, query.offset)
+        .await
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn list_anime_aliases(
+    mikan_id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<Vec<AnimeAlias>, AppError> {
+    AnimeAliasRepository::new(&pool)
+        .list_by_mikan_id(mikan_id)
+        .await
+}
+
+/// 为番剧添加别名（如英文/罗马音标题），供按标题匹配的资源来源使用
+#[command(rename_all = "snake_case")]
+pub async fn add_anime_alias(
+    mikan_id: i64,
+    alias: String,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<AnimeAlias, AppError> {
+    let alias = alias.trim();
+    if alias.is_empty() {
+        return Err(AppError::Input(InputError::Invalid(
+            "别名不能为空".to_string(),
+        )));
+    }
+    if AnimeRepository::new(&pool)
+        .get_by_id(mikan_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::Domain(DomainError::NotFound {
+            resource_type: "anime".to_string(),
+            resource_id: mikan_id,
+        }));
+    }
+    let repo = AnimeAliasRepository::new(&pool);
+    repo.create(&AnimeAlias {
+        id: None,
+        mikan_id,
+        alias: alias.to_string(),
+        created_at: Some(chrono::Utc::now().timestamp_millis()),
+    })
+    .await?;
+    repo.get_by_alias(mikan_id, alias)
+        .await?
+        .ok_or_else(|| AppError::Domain(DomainError::Other("别名创建失败".to_string())))
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn remove_anime_alias(id: i64, pool: State<'_, Arc<SqlitePool>>) -> Result<(), AppError> {
+    AnimeAliasRepository::new(&pool).delete(id).await
+}
+
+/// 列出 Mikan 番剧与 Bangumi 条目的映射，可按任一ID过滤
+#[command(rename_all = "snake_case")]
+pub async fn list_anime_mappings(
+    mikan_id: Option<i64>,
+    bangumi_id: Option<i64>,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<Vec<AnimeBangumiMapping>, AppError> {
+    let repo = AnimeMappingRepository::new(&pool);
+    match (mikan_id, bangumi_id) {
+        (Some(mikan_id), _) => repo.list_by_mikan_id(mikan_id).await,
+        (None, Some(bangumi_id)) => repo.list_by_bangumi_id(bangumi_id).await,
+        (None, None) => repo.list(0, 0).await,
+    }
+}
+
+/// 添加映射。番剧一旦有映射，只按映射关联 Bangumi 条目，忽略 Mikan 页面上的链接。
+/// 已存在的映射会更新季度与集数偏移，如分割放送的第二部分以第13集起算时 episode_offset 为12
+#[command(rename_all = "snake_case")]
+pub async fn add_anime_mapping(
+    mikan_id: i64,
+    bangumi_id: i64,
+    season: Option<i32>,
+    episode_offset: Option<i32>,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<AnimeBangumiMapping, AppError> {
+    if bangumi_id <= 0 {
+        return Err(AppError::Input(InputError::Invalid(
+            "bangumi_id无效".to_string(),
+        )));
+    }
+    if AnimeRepository::new(&pool)
+        .get_by_id(mikan_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::Domain(DomainError::NotFound {
+            resource_type: "anime".to_string(),
+            resource_id: mikan_id,
+        }));
+    }
+    let repo = AnimeMappingRepository::new(&pool);
+    repo.create(&AnimeBangumiMapping {
+        id: None,
+        mikan_id,
+        bangumi_id,
+        created_at: Some(chrono::Utc::now().timestamp_millis()),
+        season,
+        episode_offset: episode_offset.unwrap_or(0),
+    })
+    .await?;
+    repo.get_by_pair(mikan_id, bangumi_id)
+        .await?
+        .ok_or_else(|| AppError::Domain(DomainError::Other("映射创建失败".to_string())))
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn remove_anime_mapping(
+    id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<(), AppError> {
+    AnimeMappingRepository::new(&pool).delete(id).await
+}
+
+/// 为缺少 bangumi_id 的番剧搜索 Bangumi 条目，高置信度的自动关联，其余生成待确认候选
+#[command(rename_all = "snake_case")]
+pub async fn reconcile_bangumi_ids(
+    limit: Option<i64>,
+    pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, crate::config::Config>,
+) -> Result<ReconcileSummary, AppError> {
+    let service = BangumiService::new(pool.inner().clone(), config.inner().clone());
+    service.reconcile_missing_ids(limit.unwrap_or(50)).await
+}
+
+/// 待确认的 Bangumi 候选条目，可按番剧过滤
+#[command(rename_all = "snake_case")]
+pub async fn list_bangumi_match_candidates(
+    mikan_id: Option<i64>,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<Vec<BangumiMatchCandidate>, AppError> {
+    BangumiMatchRepository::new(&pool)
+        .list_pending(mikan_id)
+        .await
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn confirm_bangumi_match(
+    candidate_id: i64,
+    allow_shared: Option<bool>,
+    pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, crate::config::Config>,
+) -> Result<BangumiMatchCandidate, AppError> {
+    let service = BangumiService::new(pool.inner().clone(), config.inner().clone());
+    service
+        .confirm_match(candidate_id, allow_shared.unwrap_or(false))
+        .await
+}
+
+#[command(rename_all = "snake_case")]
+pub async fn reject_bangumi_match(
+    candidate_id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, crate::config::Config>,
+) -> Result<BangumiMatchCandidate, AppError> {
     let service = BangumiService::new(pool.inner().clone(), config.inner().clone());
+    service.reject_match(candidate_id).await
+}
+
+/// 资源的种子文件清单。fetch 为 true 且尚未解析时立即下载种子，用于下载前选择文件
+#[command(rename_all = "snake_case")]
+pub async fn get_torrent_manifest(
+    resource_id: i64,
+    fetch: Option<bool>,
+    pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, crate::config::Config>,
+) -> Result<Option<TorrentManifest>, AppError> {
+    let service = TorrentService::new(pool.inner().clone(), config.inner());
     service
-        .aggregate_resources(bangumi_id, None, resolution, subtitle_type, limit, offset)
+        .get_manifest(resource_id, fetch.unwrap_or(false))
         .await
 }
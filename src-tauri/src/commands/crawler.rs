@@ -1,8 +1,17 @@
 use crate::{
+    config::Config,
+    core::source_registry::{SourceInfo, SourceRegistry},
     error::{AppError, TaskError},
-    models::CrawlerTask,
-    repositories::{base::Repository, crawler_task::CrawlerTaskRepository},
-    types::crawler::{CrawlerTaskCreate, TaskResponse},
+    models::{CrawlerTask, ParseQuarantine},
+    repositories::{
+        base::Repository, crawler_task::CrawlerTaskRepository,
+        parse_quarantine::ParseQuarantineRepository,
+    },
+    services::parser_service::ParserService,
+    types::crawler::{
+        CrawlResultSummary, CrawlerTaskCreate, ParserEvaluation, QuarantineReparseResult,
+        TaskResponse,
+    },
 };
 use sqlx::SqlitePool;
 use std::sync::Arc;
@@ -15,7 +24,7 @@ fn convert_to_response(task: CrawlerTask) -> TaskResponse {
         task_type: task.task_type.into(),
         status: task.status.into(),
         parameters: task.parameters,
-        result_summary: task.result_summary,
+        result_summary: CrawlResultSummary::parse(task.result_summary.as_deref()),
         created_at: Some(task.created_at.unwrap_or_default()),
         started_at: task.started_at,
         completed_at: task.completed_at,
@@ -32,10 +41,12 @@ fn convert_to_response(task: CrawlerTask) -> TaskResponse {
 pub async fn create_crawler_task(
     task: CrawlerTaskCreate,
     pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, Config>,
     notify: State<'_, Arc<Notify>>,
 ) -> Result<TaskResponse, AppError> {
     crate::services::crawler_service::CrawlerService::create_task(
         pool.inner().clone(),
+        config.inner(),
         notify.inner().clone(),
         task,
     )
@@ -115,3 +126,73 @@ pub async fn delete_crawler_task(
 
     Ok(())
 }
+
+#[command(rename_all = "snake_case")]
+pub fn list_resource_sources(config: State<'_, Config>) -> Result<Vec<SourceInfo>, AppError> {
+    Ok(SourceRegistry::from_config(config.inner()).list())
+}
+
+/// 恢复失败或已取消的任务，已完成的详情页不会重复抓取
+#[command(rename_all = "snake_case")]
+pub async fn resume_crawler_task(
+    task_id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+    notify: State<'_, Arc<Notify>>,
+) -> Result<TaskResponse, AppError> {
+    let task = crate::services::crawler_service::CrawlerService::resume_task(
+        pool.inner().clone(),
+        notify.inner().clone(),
+        task_id,
+    )
+    .await?;
+    Ok(convert_to_response(task))
+}
+
+/// 列出解析结果可疑而被隔离的页面，按隔离时间倒序
+#[command(rename_all = "snake_case")]
+pub async fn list_quarantined_pages(
+    page: Option<i64>,
+    page_size: Option<i64>,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<Vec<ParseQuarantine>, AppError> {
+    let page = page.unwrap_or(1).max(1);
+    let page_size = page_size.unwrap_or(20).max(1);
+    ParseQuarantineRepository::new(&pool)
+        .list(page_size, (page - 1) * page_size)
+        .await
+}
+
+/// 用当前解析器重新解析隔离页面，解析结果可信时入库
+#[command(rename_all = "snake_case")]
+pub async fn reparse_quarantined_page(
+    id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+    config: State<'_, Config>,
+) -> Result<QuarantineReparseResult, AppError> {
+    crate::services::crawler_service::CrawlerService::reparse_quarantined(
+        pool.inner().clone(),
+        config.inner(),
+        id,
+    )
+    .await
+}
+
+/// 丢弃隔离页面
+#[command(rename_all = "snake_case")]
+pub async fn discard_quarantined_page(
+    id: i64,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<(), AppError> {
+    ParseQuarantineRepository::new(&pool).delete(id).await
+}
+
+/// 内部命令：用当前解析器重新解析全部资源标题，统计与已存储字段的不一致率
+#[command(rename_all = "snake_case")]
+pub async fn evaluate_text_parser(
+    sample_limit: Option<i64>,
+    pool: State<'_, Arc<SqlitePool>>,
+) -> Result<ParserEvaluation, AppError> {
+    ParserService::new(pool.inner().clone())
+        .evaluate(sample_limit.unwrap_or(20).max(0) as usize)
+        .await
+}
@@ -1,6 +1,10 @@
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use toml;
 
+const DEFAULT_SCHEDULE_TIMEZONE: &str = "Asia/Shanghai";
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub db_url: String,
@@ -11,6 +15,65 @@ pub struct Config {
     pub bangumi_sub_refresh_interval: Option<i64>,
     pub bangumi_nonsub_refresh_interval: Option<i64>,
     pub bangumi_calendar_refresh_interval: Option<i64>,
+    // 后台下载种子解析文件清单的间隔（单位：秒）与每批数量，间隔<=0时关闭
+    pub torrent_metadata_interval: Option<i64>,
+    pub torrent_metadata_batch: Option<i64>,
+    // 资源来源配置，键为来源名称（如 mikan），未配置的来源使用默认值
+    pub sources: Option<HashMap<String, SourceConfig>>,
+    // Torznab 索引器（Jackett/Prowlarr 等），每个端点注册为一个资源来源
+    pub torznab_endpoints: Option<Vec<TorznabEndpoint>>,
+    // 抓取资源站时的HTTP参数
+    pub http: Option<HttpConfig>,
+    // 定时任务按此时区划分日期，IANA 名称，如 "Asia/Shanghai"
+    pub schedule_timezone: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SourceConfig {
+    pub enabled: Option<bool>,
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HttpConfig {
+    // 请求超时与连接超时（单位：秒）
+    pub timeout_secs: Option<u64>,
+    pub connect_timeout_secs: Option<u64>,
+    pub user_agent: Option<String>,
+    // 429/5xx 与网络错误的最大重试次数，退避基数（单位：毫秒）
+    pub max_retries: Option<u32>,
+    pub backoff_base_ms: Option<u64>,
+    // 每个host每秒请求数与突发上限，<=0 表示不限速
+    pub rate_per_sec: Option<f64>,
+    pub burst: Option<u32>,
+    // 按host覆盖每秒请求数，如 { "mikanani.me" = 1.0 }
+    pub host_rates: Option<HashMap<String, f64>>,
+    // ETag/Last-Modified 校验值存储文件，缺省存放在应用数据目录
+    pub validator_store: Option<String>,
+    // live：正常请求；record：请求并保存响应到fixture目录；replay：只从fixture目录读取，不访问网络
+    pub mode: Option<HttpMode>,
+    pub fixture_dir: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMode {
+    #[default]
+    Live,
+    Record,
+    Replay,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TorznabEndpoint {
+    // 来源名称，写入 resource.source，不能与内置来源重名
+    pub name: String,
+    // 如 http://127.0.0.1:9117/api/v2.0/indexers/all/results/torznab/api
+    pub url: String,
+    pub api_key: Option<String>,
+    // Torznab 分类，缺省为 5070（TV/Anime）
+    pub categories: Option<Vec<i64>>,
+    pub enabled: Option<bool>,
 }
 
 impl Default for Config {
@@ -23,6 +86,12 @@ impl Default for Config {
             bangumi_sub_refresh_interval: Some(3600),       // 1小时
             bangumi_nonsub_refresh_interval: Some(43200),   // 12小时
             bangumi_calendar_refresh_interval: Some(86400), // 24小时
+            torrent_metadata_interval: Some(600),           // 10分钟
+            torrent_metadata_batch: Some(20),
+            sources: None,
+            torznab_endpoints: None,
+            http: None,
+            schedule_timezone: Some(DEFAULT_SCHEDULE_TIMEZONE.to_string()),
         }
     }
 }
@@ -38,4 +107,27 @@ impl Config {
             Self::default()
         }
     }
+
+    /// 定时任务使用的时区，未配置或名称无效时使用北京时间
+    pub fn schedule_timezone(&self) -> Tz {
+        let name = self
+            .schedule_timezone
+            .as_deref()
+            .unwrap_or(DEFAULT_SCHEDULE_TIMEZONE);
+        name.parse().unwrap_or_else(|_| {
+            tracing::warn!("无效的时区{}，使用{}", name, DEFAULT_SCHEDULE_TIMEZONE);
+            chrono_tz::Asia::Shanghai
+        })
+    }
+
+    pub fn http(&self) -> HttpConfig {
+        self.http.clone().unwrap_or_default()
+    }
+
+    pub fn source(&self, name: &str) -> SourceConfig {
+        self.sources
+            .as_ref()
+            .and_then(|sources| sources.get(name).cloned())
+            .unwrap_or_default()
+    }
 }
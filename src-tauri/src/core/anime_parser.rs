@@ -1,9 +1,109 @@
-use crate::core::AnimeData;
-use crate::error::Result;
+use crate::core::{AnimeData, FeedItem};
+use crate::error::{AppError, DomainError, Result};
+use crate::models::{Resource, SubtitleGroup};
+use crate::types::crawler::{CrawlerTaskCreate, SeasonName};
+use chrono_tz::Tz;
+
+/// 订阅源条目关联番剧与字幕组的方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FeedMapping {
+    /// 抓取条目的资源页，从中解析番剧与字幕组（Mikan）
+    EpisodePage,
+    /// 按标题匹配已入库番剧，按发布组名称关联字幕组
+    TitleMatch,
+}
+
+/// 解析结果的可疑之处，对应页面会被隔离保存以便排查站点改版
+#[derive(Debug, Clone)]
+pub struct ParseIssue {
+    pub reason: String,
+    /// 为true时解析结果不可信，不写入数据库
+    pub blocking: bool,
+}
+
+/// 资源来源：负责构建各抓取模式的URL并解析页面，按名称注册到 SourceRegistry
+pub trait AnimeParser: Send + Sync {
+    /// 来源名称，写入 resource.source
+    fn name(&self) -> &str;
+    /// 站点根地址
+    fn base_url(&self) -> &str;
+    /// 站点发布时间所用时区，页面与订阅源中未带时区的时间按此解析
+    fn timezone(&self) -> Tz {
+        Tz::UTC
+    }
+
+    /// 首页列表URL，不支持时返回None
+    fn homepage_url(&self) -> Option<String> {
+        None
+    }
+    /// 季度列表URL，不支持时返回None
+    fn season_url(&self, _year: i64, _season: &SeasonName) -> Option<String> {
+        None
+    }
+    /// 番剧详情页URL
+    fn detail_url(&self, _mikan_id: i64) -> Option<String> {
+        None
+    }
+    /// 从详情页URL中解析番剧ID
+    fn detail_id(&self, _url: &str) -> Option<i64> {
+        None
+    }
+    /// 字幕组完整资源表的URL，用于补全详情页中被截断的资源表
+    fn expand_url(&self, _mikan_id: i64, _group_id: i64) -> Option<String> {
+        None
+    }
+    /// 根据任务参数构建订阅源URL
+    fn feed_url(&self, _params: &CrawlerTaskCreate) -> Option<String> {
+        None
+    }
+
+    /// 订阅源条目的关联方式
+    fn feed_mapping(&self) -> FeedMapping {
+        FeedMapping::TitleMatch
+    }
+
+    /// 按番剧搜索订阅源时，优先使用番剧别名（如英文标题）而非中文标题作为关键词
+    fn prefers_alias_keyword(&self) -> bool {
+        false
+    }
 
-pub trait AnimeParser {
     /// 解析番剧列表页，返回详情页URL列表
-    fn parse_list(&self, html: &str) -> Result<Vec<String>>;
+    fn parse_list(&self, _html: &str) -> Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+    /// 检查列表页解析结果，可疑时返回原因
+    fn check_list(&self, urls: &[String]) -> Option<ParseIssue> {
+        urls.is_empty().then(|| ParseIssue {
+            reason: "列表页未解析到详情页链接".to_string(),
+            blocking: true,
+        })
+    }
+    /// 检查详情页解析结果，可疑时返回原因
+    fn check_detail(&self, _data: &AnimeData) -> Option<ParseIssue> {
+        None
+    }
     /// 解析番剧详情页，返回结构化数据，需传入mikan_id
-    fn parse_detail(&self, html: &str, mikan_id: i64) -> Result<AnimeData>;
+    fn parse_detail(&self, _html: &str, _mikan_id: i64) -> Result<AnimeData> {
+        Err(AppError::Domain(DomainError::Other(format!(
+            "来源{}不支持解析详情页",
+            self.name()
+        ))))
+    }
+    /// 解析字幕组完整资源表（expand_url 返回的页面片段）
+    fn parse_expanded_table(
+        &self,
+        _html: &str,
+        _mikan_id: i64,
+        _group_id: i64,
+    ) -> Result<Vec<Resource>> {
+        Ok(Vec::new())
+    }
+    /// 解析订阅源，返回尚未关联番剧的资源条目
+    fn parse_feed(&self, _xml: &str) -> Result<Vec<FeedItem>> {
+        Ok(Vec::new())
+    }
+    /// 解析资源页，返回资源所属番剧的mikan_id与字幕组
+    fn parse_episode_page(&self, _html: &str) -> Option<(i64, SubtitleGroup)> {
+        None
+    }
 }
@@ -0,0 +1,127 @@
+use crate::core::anime_parser::{AnimeParser, FeedMapping};
+use crate::core::rss::parse_rss;
+use crate::core::text_parser;
+use crate::core::FeedItem;
+use crate::error::Result;
+use crate::types::crawler::CrawlerTaskCreate;
+
+pub const SOURCE_NAME: &str = "dmhy";
+pub const DEFAULT_BASE_URL: &str = "https://share.dmhy.org";
+// 动漫花园"动画"分类
+const ANIME_SORT_ID: &str = "2";
+
+/// 动漫花园：只提供资源订阅源，条目按标题匹配到已入库番剧
+pub struct DmhyParser {
+    pub base_url: String,
+}
+
+impl DmhyParser {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl AnimeParser for DmhyParser {
+    fn name(&self) -> &str {
+        SOURCE_NAME
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // 站点以北京时间发布
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Asia::Shanghai
+    }
+
+    /// 动画分类RSS，指定keyword时为搜索结果RSS
+    fn feed_url(&self, params: &CrawlerTaskCreate) -> Option<String> {
+        let url = format!("{}/topics/rss/rss.xml", self.base_url);
+        let mut query = vec![("sort_id", ANIME_SORT_ID)];
+        if let Some(keyword) = params.keyword.as_deref().filter(|k| !k.trim().is_empty()) {
+            query.push(("keyword", keyword.trim()));
+        }
+        reqwest::Url::parse_with_params(&url, &query)
+            .ok()
+            .map(|url| url.to_string())
+    }
+
+    fn feed_mapping(&self) -> FeedMapping {
+        FeedMapping::TitleMatch
+    }
+
+    fn parse_feed(&self, xml: &str) -> Result<Vec<FeedItem>> {
+        let doc = parse_rss(xml)?;
+        Ok(doc
+            .channel
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                // 动漫花园的enclosure即磁力链接
+                let magnet_url = item
+                    .enclosure
+                    .map(|e| e.url)
+                    .filter(|url| url.starts_with("magnet:"))?;
+                let magnet_hash = text_parser::parse_magnet_hash(&magnet_url);
+                let group_name = text_parser::parse_release_group(&item.title)
+                    .or_else(|| item.author.map(|a| a.trim().to_string()))
+                    .filter(|name| !name.is_empty());
+                Some(FeedItem {
+                    page_url: item.link,
+                    magnet_url: Some(magnet_url),
+                    magnet_hash,
+                    torrent_url: None,
+                    file_size: None,
+                    size_bytes: None,
+                    release_date: item.pub_date.as_deref().and_then(|date| {
+                        text_parser::parse_datetime_to_timestamp(date, self.timezone())
+                    }),
+                    subtitle_type: None,
+                    seeders: None,
+                    leechers: None,
+                    group_name,
+                    title: item.title.trim().to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feed_reads_magnet_enclosures() {
+        let parser = DmhyParser::new(DEFAULT_BASE_URL);
+        let items = parser
+            .parse_feed(include_str!("testdata/dmhy_feed.xml"))
+            .unwrap();
+        // 非磁力链接的enclosure被跳过
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert!(item
+            .magnet_url
+            .as_deref()
+            .unwrap()
+            .starts_with("magnet:?xt=urn:btih:0123456789ABCDEF"));
+        assert_eq!(
+            item.magnet_hash.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(item.torrent_url, None);
+        assert_eq!(
+            item.page_url.as_deref(),
+            Some("http://share.dmhy.org/topics/view/652001_Sousou_no_Frieren_-_05.html")
+        );
+        assert_eq!(item.group_name.as_deref(), Some("喵萌奶茶屋&LoliHouse"));
+        // 北京时间 20:30
+        assert_eq!(item.release_date, Some(1_697_286_600_000));
+        assert!(item
+            .title
+            .starts_with("[喵萌奶茶屋&LoliHouse] 葬送的芙莉莲"));
+    }
+}
@@ -1,19 +1,577 @@
-use crate::error::Result;
-use reqwest::Client;
+use crate::config::{HttpConfig, HttpMode};
+use crate::error::{ApiError, AppError, Result};
+use once_cell::sync::Lazy;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, RETRY_AFTER,
+};
+use reqwest::{Client, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_BACKOFF_BASE_MS: u64 = 500;
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+// 每个host默认每秒2个请求，允许突发4个
+const DEFAULT_RATE_PER_SEC: f64 = 2.0;
+const DEFAULT_BURST: f64 = 4.0;
+// 校验值存储最多保留的URL数，超出时丢弃最早写入的
+const MAX_VALIDATORS: usize = 2000;
+// 携带凭据的查询参数：Torznab 的 apikey、Mikan 订阅的 token、PT 站的 passkey 等
+const CREDENTIAL_PARAMS: &[&str] = &["apikey", "token", "passkey", "key"];
+
+// =============================================================================
+// 按host的令牌桶限速，进程内所有 HttpFetcher 共享，多个任务并发时同样生效
+// =============================================================================
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+static BUCKETS: Lazy<Mutex<HashMap<String, TokenBucket>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 串行化校验值文件的读写
+static VALIDATOR_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 条件请求使用的校验值
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub updated_at: i64,
+}
+
+/// 已读取完整响应体的HTTP响应，录制/回放时以JSON保存
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub url: String,
+    pub status: u16,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    fn validators(&self) -> Option<Validators> {
+        if self.etag.is_none() && self.last_modified.is_none() {
+            return None;
+        }
+        Some(Validators {
+            etag: self.etag.clone(),
+            last_modified: self.last_modified.clone(),
+            updated_at: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+}
+
+/// 条件请求结果
+#[derive(Debug)]
+pub enum FetchOutcome {
+    /// 服务端返回304，内容自上次保存校验值后未变化
+    NotModified,
+    /// 新内容；处理成功后调用 `save_validators` 保存校验值
+    Fetched {
+        body: String,
+        validators: Option<Validators>,
+    },
+}
 
 pub struct HttpFetcher {
     client: Client,
+    max_retries: u32,
+    backoff_base: Duration,
+    rate_per_sec: f64,
+    burst: f64,
+    host_rates: HashMap<String, f64>,
+    validator_store: Option<PathBuf>,
+    mode: HttpMode,
+    fixture_dir: Option<PathBuf>,
 }
 
 impl HttpFetcher {
-    pub fn new() -> Self {
+    pub fn new(config: &HttpConfig) -> Self {
+        let user_agent = config
+            .user_agent
+            .clone()
+            .unwrap_or_else(|| format!("ikuyo/{}", env!("CARGO_PKG_VERSION")));
+        let client = Client::builder()
+            .timeout(Duration::from_secs(
+                config.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS),
+            ))
+            .connect_timeout(Duration::from_secs(
+                config
+                    .connect_timeout_secs
+                    .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS),
+            ))
+            .user_agent(user_agent)
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::error!("构建HTTP客户端失败，使用默认配置: {}", e);
+                Client::new()
+            });
         Self {
-            client: Client::new(),
+            client,
+            max_retries: config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES),
+            backoff_base: Duration::from_millis(
+                config.backoff_base_ms.unwrap_or(DEFAULT_BACKOFF_BASE_MS),
+            ),
+            rate_per_sec: config.rate_per_sec.unwrap_or(DEFAULT_RATE_PER_SEC),
+            burst: config
+                .burst
+                .map(f64::from)
+                .unwrap_or(DEFAULT_BURST)
+                .max(1.0),
+            host_rates: config.host_rates.clone().unwrap_or_default(),
+            validator_store: config.validator_store.as_ref().map(PathBuf::from),
+            mode: config.mode.clone().unwrap_or_default(),
+            fixture_dir: config.fixture_dir.as_ref().map(PathBuf::from),
         }
     }
 
     pub async fn fetch(&self, url: &str) -> Result<String> {
-        let resp = self.client.get(url).send().await?.text().await?;
-        Ok(resp)
+        let resp = self.get(url).await?;
+        Ok(Self::check_status(resp)?.body)
+    }
+
+    /// 获取二进制内容（如 .torrent 文件），非2xx视为错误。
+    /// 录制/回放时以 fixture 同名的 .bin 文件保存
+    pub async fn fetch_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let fixture = self.fixture_path(url).map(|p| p.with_extension("bin"));
+        if self.mode == HttpMode::Replay {
+            let path = fixture.ok_or_else(|| {
+                AppError::Api(ApiError::Request("回放模式未配置fixture目录".to_string()))
+            })?;
+            return std::fs::read(&path).map_err(|_| {
+                AppError::Api(ApiError::Request(format!(
+                    "回放模式下缺少fixture: {} ({})",
+                    redact_url(url),
+                    path.display()
+                )))
+            });
+        }
+        let resp = self.send(url, HeaderMap::new()).await?;
+        let status = resp.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(AppError::Api(ApiError::External(format!(
+                "请求{}返回状态码{}",
+                redact_url(url),
+                status
+            ))));
+        }
+        let body = resp
+            .bytes()
+            .await
+            .map_err(|e| request_error(url, e))?
+            .to_vec();
+        if let (HttpMode::Record, Some(path)) = (&self.mode, fixture) {
+            let result = path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(&path, &body));
+            if let Err(e) = result {
+                tracing::warn!("保存fixture {}失败: {}", path.display(), e);
+            }
+        }
+        Ok(body)
+    }
+
+    /// 返回状态码与响应体，非2xx不视为错误，由调用方处理
+    pub async fn get(&self, url: &str) -> Result<HttpResponse> {
+        self.request(url, HeaderMap::new()).await
+    }
+
+    /// 带 If-None-Match / If-Modified-Since 的条件请求
+    pub async fn fetch_conditional(&self, url: &str) -> Result<FetchOutcome> {
+        let mut headers = HeaderMap::new();
+        // 录制与回放均需要完整响应体，不发条件请求
+        let validators = match self.mode {
+            HttpMode::Live => self.load_validators(url),
+            HttpMode::Record | HttpMode::Replay => None,
+        };
+        if let Some(validators) = validators {
+            if let Some(value) = validators
+                .etag
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(IF_NONE_MATCH, value);
+            }
+            if let Some(value) = validators
+                .last_modified
+                .as_deref()
+                .and_then(|v| HeaderValue::from_str(v).ok())
+            {
+                headers.insert(IF_MODIFIED_SINCE, value);
+            }
+        }
+        let resp = self.request(url, headers).await?;
+        if resp.status == StatusCode::NOT_MODIFIED.as_u16() {
+            return Ok(FetchOutcome::NotModified);
+        }
+        let resp = Self::check_status(resp)?;
+        let validators = match self.mode {
+            HttpMode::Live => resp.validators(),
+            HttpMode::Record | HttpMode::Replay => None,
+        };
+        Ok(FetchOutcome::Fetched {
+            body: resp.body,
+            validators,
+        })
+    }
+
+    /// 保存URL的校验值，应在内容处理成功后调用，避免处理失败后被304跳过。
+    /// 存储文件以遮盖凭据后的URL为键，不保存 apikey/token 等
+    pub fn save_validators(&self, url: &str, validators: &Validators) {
+        let Some(path) = &self.validator_store else {
+            return;
+        };
+        let _guard = VALIDATOR_LOCK.lock().unwrap();
+        let mut store = read_validator_store(path);
+        store.insert(redact_url(url), validators.clone());
+        if store.len() > MAX_VALIDATORS {
+            let mut entries: Vec<_> = store.into_iter().collect();
+            entries.sort_by_key(|(_, v)| std::cmp::Reverse(v.updated_at));
+            entries.truncate(MAX_VALIDATORS);
+            store = entries.into_iter().collect();
+        }
+        let result = serde_json::to_string(&store)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(path, content).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            tracing::warn!("保存HTTP校验值失败: {}", e);
+        }
+    }
+
+    fn load_validators(&self, url: &str) -> Option<Validators> {
+        let path = self.validator_store.as_ref()?;
+        let _guard = VALIDATOR_LOCK.lock().unwrap();
+        read_validator_store(path).remove(&redact_url(url))
+    }
+
+    fn check_status(resp: HttpResponse) -> Result<HttpResponse> {
+        if resp.is_success() {
+            Ok(resp)
+        } else {
+            Err(AppError::Api(ApiError::External(format!(
+                "请求{}返回状态码{}",
+                redact_url(&resp.url),
+                resp.status
+            ))))
+        }
+    }
+
+    /// 按模式发送请求：回放模式只读取fixture，录制模式在真实请求后保存fixture
+    async fn request(&self, url: &str, headers: HeaderMap) -> Result<HttpResponse> {
+        if self.mode == HttpMode::Replay {
+            return self.load_fixture(url);
+        }
+        let resp = self.send(url, headers).await?;
+        let header = |name| {
+            resp.headers()
+                .get(name)
+                .and_then(|v: &HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let mut response = HttpResponse {
+            url: url.to_string(),
+            status: resp.status().as_u16(),
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            body: String::new(),
+        };
+        response.body = resp.text().await.map_err(|e| request_error(url, e))?;
+        if self.mode == HttpMode::Record {
+            self.save_fixture(&response);
+        }
+        Ok(response)
+    }
+
+    fn fixture_path(&self, url: &str) -> Option<PathBuf> {
+        let dir = self.fixture_dir.as_ref()?;
+        Some(dir.join(fixture_file_name(url)))
+    }
+
+    fn load_fixture(&self, url: &str) -> Result<HttpResponse> {
+        let path = self.fixture_path(url).ok_or_else(|| {
+            AppError::Api(ApiError::Request("回放模式未配置fixture目录".to_string()))
+        })?;
+        let content = std::fs::read_to_string(&path).map_err(|_| {
+            AppError::Api(ApiError::Request(format!(
+                "回放模式下缺少fixture: {} ({})",
+                redact_url(url),
+                path.display()
+            )))
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    fn save_fixture(&self, response: &HttpResponse) {
+        let Some(path) = self.fixture_path(&response.url) else {
+            return;
+        };
+        // fixture 目录会被提交共享，不保存凭据
+        let response = HttpResponse {
+            url: redact_url(&response.url),
+            ..response.clone()
+        };
+        let result = serde_json::to_string_pretty(&response)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(&path, content).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            tracing::warn!("保存fixture {}失败: {}", path.display(), e);
+        }
+    }
+
+    /// 限速后发送请求，429/5xx 与超时、连接错误按指数退避重试，优先遵循 Retry-After
+    async fn send(&self, url: &str, headers: HeaderMap) -> Result<Response> {
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_string))
+            .unwrap_or_default();
+        let mut attempt = 0;
+        loop {
+            self.acquire(&host).await;
+            let result = self.client.get(url).headers(headers.clone()).send().await;
+            let retry_after = match result {
+                Ok(resp)
+                    if resp.status() == StatusCode::TOO_MANY_REQUESTS
+                        || resp.status().is_server_error() =>
+                {
+                    if attempt >= self.max_retries {
+                        return Ok(resp);
+                    }
+                    tracing::warn!(
+                        "请求{}返回状态码{}，准备重试",
+                        redact_url(url),
+                        resp.status()
+                    );
+                    resp.headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| v.trim().parse::<u64>().ok())
+                        .map(Duration::from_secs)
+                }
+                Ok(resp) => return Ok(resp),
+                Err(e) if (e.is_timeout() || e.is_connect()) && attempt < self.max_retries => {
+                    tracing::warn!("请求{}失败: {}，准备重试", redact_url(url), e.without_url());
+                    None
+                }
+                Err(e) => return Err(request_error(url, e)),
+            };
+            let delay = retry_after
+                .unwrap_or_else(|| self.backoff_base * 2u32.saturating_pow(attempt))
+                .min(MAX_BACKOFF);
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// 从host对应的令牌桶取一个令牌，不足时等待补充；速率<=0表示不限速
+    async fn acquire(&self, host: &str) {
+        let rate = self
+            .host_rates
+            .get(host)
+            .copied()
+            .unwrap_or(self.rate_per_sec);
+        if rate <= 0.0 {
+            return;
+        }
+        loop {
+            let wait = {
+                let mut buckets = BUCKETS.lock().unwrap();
+                let bucket = buckets
+                    .entry(host.to_string())
+                    .or_insert_with(|| TokenBucket {
+                        tokens: self.burst,
+                        last_refill: Instant::now(),
+                    });
+                let now = Instant::now();
+                let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * rate).min(self.burst);
+                bucket.last_refill = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / rate)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 遮盖URL中携带凭据的查询参数，用于写入任务摘要、日志或fixture
+pub fn redact_url(url: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    let mut redacted = false;
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(k, v)| {
+            if CREDENTIAL_PARAMS.iter().any(|p| k.eq_ignore_ascii_case(p)) {
+                redacted = true;
+                (k.into_owned(), "***".to_string())
+            } else {
+                (k.into_owned(), v.into_owned())
+            }
+        })
+        .collect();
+    if !redacted {
+        return url.to_string();
+    }
+    parsed.query_pairs_mut().clear().extend_pairs(pairs);
+    parsed.to_string()
+}
+
+/// reqwest 的错误信息带有完整URL，替换为遮盖凭据后的URL
+fn request_error(url: &str, e: reqwest::Error) -> AppError {
+    AppError::Api(ApiError::Request(format!(
+        "请求{}失败: {}",
+        redact_url(url),
+        e.without_url()
+    )))
+}
+
+/// fixture文件名：URL中的可读部分加上完整URL的FNV-1a哈希，保证稳定且不冲突。
+/// 按去掉凭据后的URL计算，录制与回放使用不同的 apikey/token 也能对应
+fn fixture_file_name(url: &str) -> String {
+    let url = redact_url(url);
+    let url = url.as_str();
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in url.as_bytes() {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    let readable: String = url
+        .split_once("://")
+        .map_or(url, |(_, rest)| rest)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .take(80)
+        .collect();
+    format!("{}-{:016x}.json", readable, hash)
+}
+
+fn read_validator_store(path: &Path) -> HashMap<String, Validators> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_name_ignores_credentials() {
+        let recorded = fixture_file_name("https://mikanani.me/RSS/MyBangumi?token=recorded");
+        let replayed = fixture_file_name("https://mikanani.me/RSS/MyBangumi?token=other");
+        assert_eq!(recorded, replayed);
+        assert!(!recorded.contains("recorded"));
+        assert_ne!(
+            recorded,
+            fixture_file_name("https://mikanani.me/RSS/Bangumi?bangumiId=3141")
+        );
+    }
+
+    #[tokio::test]
+    async fn request_errors_hide_credentials() {
+        let fetcher = HttpFetcher::new(&HttpConfig {
+            max_retries: Some(0),
+            rate_per_sec: Some(0.0),
+            ..Default::default()
+        });
+        // 连接失败：reqwest 的错误信息不能带出原始URL
+        let error = fetcher
+            .fetch("http://127.0.0.1:9/api?t=search&apikey=secret")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(!error.contains("secret"), "{}", error);
+        assert!(error.contains("apikey=***"), "{}", error);
+
+        // 非2xx状态码
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let _ = socket
+                    .write_all(b"HTTP/1.1 403 Forbidden\r\nContent-Length: 0\r\n\r\n")
+                    .await;
+            }
+        });
+        let url = format!("http://{}/api?t=search&apikey=secret", addr);
+        for error in [
+            fetcher.fetch(&url).await.unwrap_err().to_string(),
+            fetcher.fetch_bytes(&url).await.unwrap_err().to_string(),
+        ] {
+            assert!(!error.contains("secret"), "{}", error);
+            assert!(error.contains("403"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn validator_store_hides_credentials() {
+        let path =
+            std::env::temp_dir().join(format!("ikuyo-validators-{}.json", std::process::id()));
+        let fetcher = HttpFetcher::new(&HttpConfig {
+            validator_store: Some(path.to_string_lossy().into_owned()),
+            ..Default::default()
+        });
+        let url = "https://mikanani.me/RSS/MyBangumi?token=secret";
+        let validators = Validators {
+            etag: Some("\"abc\"".to_string()),
+            ..Default::default()
+        };
+        fetcher.save_validators(url, &validators);
+        let content = std::fs::read_to_string(&path).unwrap();
+        let loaded = fetcher.load_validators(url);
+        std::fs::remove_file(&path).ok();
+        assert!(!content.contains("secret"), "{}", content);
+        assert_eq!(loaded.and_then(|v| v.etag).as_deref(), Some("\"abc\""));
+    }
+
+    #[test]
+    fn redact_url_masks_credentials() {
+        assert_eq!(
+            redact_url("https://mikanani.me/RSS/MyBangumi?token=abc%2B123%3D%3D"),
+            "https://mikanani.me/RSS/MyBangumi?token=***"
+        );
+        assert_eq!(
+            redact_url("http://127.0.0.1:9117/api?t=search&apikey=secret&q=Frieren"),
+            "http://127.0.0.1:9117/api?t=search&apikey=***&q=Frieren"
+        );
+        assert_eq!(
+            redact_url("https://tracker.example/rss?PassKey=p&key=k"),
+            "https://tracker.example/rss?PassKey=***&key=***"
+        );
+        // 不含凭据的URL保持原样
+        let url = "https://mikanani.me/RSS/Bangumi?bangumiId=3141&subgroupid=370";
+        assert_eq!(redact_url(url), url);
     }
 }
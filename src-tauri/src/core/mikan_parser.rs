@@ -1,10 +1,18 @@
-use crate::core::anime_parser::AnimeParser;
-use crate::core::AnimeData;
+use crate::core::anime_parser::{AnimeParser, FeedMapping, ParseIssue};
+use crate::core::{rss, AnimeData, FeedItem};
 use crate::error::{AppError, DomainError, Result};
+use crate::types::crawler::{CrawlerTaskCreate, SeasonName};
 use regex::Regex;
 use scraper::{ElementRef, Html, Selector};
 use std::collections::HashSet;
 
+pub const SOURCE_NAME: &str = "mikan";
+pub const DEFAULT_BASE_URL: &str = "https://mikanani.me";
+// 详情页未解析到标题时的占位标题
+const UNKNOWN_TITLE: &str = "未知标题";
+// 展开资源表时请求的条数，取足够大的值以一次取回全部资源
+const EXPAND_TAKE: i64 = 1000;
+
 pub struct MikanParser {
     pub base_url: String,
 }
@@ -15,9 +23,80 @@ impl MikanParser {
             base_url: base_url.to_string(),
         }
     }
+
+    /// 番剧RSS，指定字幕组时只包含该字幕组的资源
+    pub fn bangumi_rss_url(&self, mikan_id: i64, subgroup_id: Option<i64>) -> String {
+        match subgroup_id {
+            Some(group_id) => format!(
+                "{}/RSS/Bangumi?bangumiId={}&subgroupid={}",
+                self.base_url, mikan_id, group_id
+            ),
+            None => format!("{}/RSS/Bangumi?bangumiId={}", self.base_url, mikan_id),
+        }
+    }
+
+    /// 用户订阅RSS（MyBangumi），token 来自 Mikan 个人订阅页
+    pub fn my_bangumi_rss_url(&self, token: &str) -> String {
+        format!("{}/RSS/MyBangumi?token={}", self.base_url, token)
+    }
 }
 
 impl AnimeParser for MikanParser {
+    fn name(&self) -> &str {
+        SOURCE_NAME
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // 站点以北京时间发布
+    fn timezone(&self) -> chrono_tz::Tz {
+        chrono_tz::Asia::Shanghai
+    }
+
+    fn homepage_url(&self) -> Option<String> {
+        Some(format!("{}/Home", self.base_url))
+    }
+
+    fn season_url(&self, year: i64, season: &SeasonName) -> Option<String> {
+        Some(format!(
+            "{}/Home/BangumiCoverFlowByDayOfWeek?year={}&seasonStr={}",
+            self.base_url,
+            year,
+            season.as_str()
+        ))
+    }
+
+    fn detail_url(&self, mikan_id: i64) -> Option<String> {
+        Some(format!("{}/Home/Bangumi/{}", self.base_url, mikan_id))
+    }
+
+    fn detail_id(&self, url: &str) -> Option<i64> {
+        url.split("/Home/Bangumi/")
+            .nth(1)?
+            .split(['#', '?'])
+            .next()?
+            .parse::<i64>()
+            .ok()
+    }
+
+    // 详情页"显示更多"按钮调用的接口
+    fn expand_url(&self, mikan_id: i64, group_id: i64) -> Option<String> {
+        Some(format!(
+            "{}/Home/ExpandEpisodeTable?bangumiId={}&subtitleGroupId={}&take={}",
+            self.base_url, mikan_id, group_id, EXPAND_TAKE
+        ))
+    }
+
+    fn feed_url(&self, params: &CrawlerTaskCreate) -> Option<String> {
+        match (&params.rss_token, params.mikan_id) {
+            (Some(token), _) => Some(self.my_bangumi_rss_url(token)),
+            (None, Some(mikan_id)) => Some(self.bangumi_rss_url(mikan_id, params.subgroup_id)),
+            (None, None) => None,
+        }
+    }
+
     fn parse_list(&self, html: &str) -> Result<Vec<String>> {
         let document = Html::parse_document(html);
         let mut urls = Vec::new();
@@ -56,19 +135,148 @@ impl AnimeParser for MikanParser {
         let document = Html::parse_document(html);
         let anime = self.parse_anime_info(&document, mikan_id);
         let (subtitle_groups, resources) = self.parse_groups_and_resources(&document, mikan_id);
+        let truncated_groups = self.parse_truncated_groups(&document);
         Ok(AnimeData {
             anime: Some(anime),
             subtitle_groups,
             resources,
+            truncated_groups,
         })
     }
+
+    /// 标题缺失或有字幕组却没有资源行时，多半是页面结构发生了变化
+    fn check_detail(&self, data: &AnimeData) -> Option<ParseIssue> {
+        let anime = data.anime.as_ref()?;
+        if anime.title.trim().is_empty() || anime.title == UNKNOWN_TITLE {
+            return Some(ParseIssue {
+                reason: "未解析到番剧标题".to_string(),
+                blocking: true,
+            });
+        }
+        if !data.subtitle_groups.is_empty() && data.resources.is_empty() {
+            return Some(ParseIssue {
+                reason: format!("解析到{}个字幕组但没有资源行", data.subtitle_groups.len()),
+                blocking: true,
+            });
+        }
+        // 部分番剧在 Mikan 上确实没有 Bangumi 链接，仍然入库
+        if anime.bangumi_id == 0 {
+            return Some(ParseIssue {
+                reason: "未解析到Bangumi ID".to_string(),
+                blocking: false,
+            });
+        }
+        None
+    }
+
+    fn parse_expanded_table(
+        &self,
+        html: &str,
+        mikan_id: i64,
+        group_id: i64,
+    ) -> Result<Vec<crate::models::Resource>> {
+        let fragment = Html::parse_fragment(html);
+        let row_selector = Selector::parse("tr")
+            .map_err(|e| AppError::Domain(DomainError::Serialization(e.to_string())))?;
+        Ok(fragment
+            .select(&row_selector)
+            .filter_map(|row| self.parse_resource_row(&row, mikan_id, group_id))
+            .collect())
+    }
+
+    fn parse_feed(&self, xml: &str) -> Result<Vec<FeedItem>> {
+        let document = rss::parse_rss(xml)?;
+        let items = document
+            .channel
+            .items
+            .into_iter()
+            .map(|item| {
+                // Mikan 的资源页链接以 info hash 结尾：/Home/Episode/{hash}
+                let magnet_hash = item
+                    .link
+                    .as_ref()
+                    .and_then(|link| link.rsplit('/').next())
+                    .filter(|hash| hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+                    .map(|hash| hash.to_lowercase());
+                let magnet_url = magnet_hash
+                    .as_ref()
+                    .map(|hash| format!("magnet:?xt=urn:btih:{}", hash));
+                let content_length = item
+                    .torrent
+                    .as_ref()
+                    .and_then(|t| t.content_length)
+                    .or_else(|| item.enclosure.as_ref().and_then(|e| e.length));
+                let release_date = item
+                    .torrent
+                    .as_ref()
+                    .and_then(|t| t.pub_date.as_deref())
+                    .and_then(|date| {
+                        crate::core::text_parser::parse_datetime_to_timestamp(date, self.timezone())
+                    });
+                FeedItem {
+                    title: item.title.trim().to_string(),
+                    page_url: item.link,
+                    magnet_url,
+                    magnet_hash,
+                    torrent_url: item.enclosure.map(|e| e.url),
+                    file_size: content_length.map(crate::core::text_parser::format_file_size),
+                    size_bytes: content_length,
+                    release_date,
+                    subtitle_type: None,
+                    seeders: None,
+                    leechers: None,
+                    group_name: None,
+                }
+            })
+            .collect();
+        Ok(items)
+    }
+
+    // 资源页：/Home/Episode/{hash}
+    fn feed_mapping(&self) -> FeedMapping {
+        FeedMapping::EpisodePage
+    }
+
+    fn parse_episode_page(&self, html: &str) -> Option<(i64, crate::models::SubtitleGroup)> {
+        let document = Html::parse_document(html);
+        let bangumi_href = self.extract_href(
+            &document,
+            &[
+                "p.bangumi-title a[href*='/Home/Bangumi/']",
+                "a[href*='/Home/Bangumi/']",
+            ],
+        )?;
+        let mikan_id = self.detail_id(&bangumi_href)?;
+        let group_selector = Selector::parse("a[href*='/Home/PublishGroup/']").ok()?;
+        let group_link = document.select(&group_selector).next()?;
+        let group_id = group_link
+            .value()
+            .attr("href")?
+            .rsplit('/')
+            .next()?
+            .parse::<i64>()
+            .ok()?;
+        let group_name = group_link.text().collect::<String>().trim().to_string();
+        if group_name.is_empty() {
+            return None;
+        }
+        Some((
+            mikan_id,
+            crate::models::SubtitleGroup {
+                id: Some(group_id),
+                name: group_name,
+                last_update: Some(chrono::Utc::now().timestamp_millis()),
+                created_at: Some(chrono::Utc::now().timestamp_millis()),
+            },
+        ))
+    }
 }
 
 impl MikanParser {
     fn parse_anime_info(&self, document: &Html, mikan_id: i64) -> crate::models::Anime {
         let title = self
             .extract_text(document, &["p.bangumi-title", "title"])
-            .unwrap_or_else(|| "未知标题".to_string())
+            .unwrap_or_else(|| UNKNOWN_TITLE.to_string())
             .replace("Mikan Project - ", "");
         let bangumi_url = self.extract_href(document, &["a[href*='bgm.tv/subject/']"]);
         let bangumi_id = bangumi_url
@@ -162,6 +370,18 @@ impl MikanParser {
         None
     }
 
+    /// 资源较多的字幕组只渲染部分行，并带有"显示更多"按钮
+    fn parse_truncated_groups(&self, document: &Html) -> Vec<i64> {
+        let selector = Selector::parse(".js-expand-episode").unwrap();
+        let mut seen = HashSet::new();
+        document
+            .select(&selector)
+            .filter_map(|element| element.value().attr("data-subtitlegroupid"))
+            .filter_map(|id| id.trim().parse::<i64>().ok())
+            .filter(|id| *id != 0 && seen.insert(*id))
+            .collect()
+    }
+
     fn parse_groups_and_resources(
         &self,
         document: &Html,
@@ -253,33 +473,32 @@ impl MikanParser {
             .and_then(|a| a.value().attr("href"))
             .map(|s| format!("{}{}", self.base_url, s));
         let file_size = Some(size_cell.text().collect::<String>().trim().to_string());
+        let size_bytes = file_size
+            .as_deref()
+            .and_then(crate::core::text_parser::parse_file_size_bytes);
         let release_date_str = date_cell.text().collect::<String>().trim().to_string();
-        let release_date = crate::core::text_parser::parse_datetime_to_timestamp(&release_date_str);
-        let magnet_hash = {
-            let re = Regex::new(r"xt=urn:btih:([a-fA-F0-9]{40})").unwrap();
-            re.captures(&magnet_url)
-                .and_then(|caps| caps.get(1).map(|m| m.as_str().to_lowercase()))
-        };
-        let episode_number = crate::core::text_parser::parse_episode_number(&resource_title);
-        let resolution = crate::core::text_parser::parse_resolution(&resource_title);
-        let subtitle_type =
-            crate::core::text_parser::parse_and_normalize_subtitle_type(&resource_title);
-        Some(crate::models::Resource {
-            id: None,
+        let release_date = crate::core::text_parser::parse_datetime_to_timestamp(
+            &release_date_str,
+            self.timezone(),
+        );
+        let magnet_hash = crate::core::text_parser::parse_magnet_hash(&magnet_url);
+        let parsed = crate::core::text_parser::parse_release(&resource_title);
+        let mut resource = crate::models::Resource {
             mikan_id,
             subtitle_group_id: group_id,
-            episode_number,
             title: resource_title,
             file_size,
-            resolution,
-            subtitle_type,
             magnet_url: Some(magnet_url),
             torrent_url,
-            play_url: None,
             magnet_hash,
             release_date,
             created_at: Some(chrono::Utc::now().timestamp_millis()),
             updated_at: Some(chrono::Utc::now().timestamp_millis()),
-        })
+            source: SOURCE_NAME.to_string(),
+            size_bytes,
+            ..Default::default()
+        };
+        parsed.apply_to(&mut resource, None);
+        Some(resource)
     }
 }
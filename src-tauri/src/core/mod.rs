@@ -1,7 +1,14 @@
 pub mod anime_parser;
+pub mod dmhy_parser;
 pub mod http_fetcher;
 pub mod mikan_parser;
+pub mod nyaa_parser;
+pub mod rss;
+pub mod source_registry;
 pub mod text_parser;
+pub mod title_matcher;
+pub mod torrent_parser;
+pub mod torznab_parser;
 
 use crate::models::{Anime, Resource, SubtitleGroup};
 // 动画、字幕组、资源等结构体（可根据models完善）
@@ -10,4 +17,58 @@ pub struct AnimeData {
     pub anime: Option<Anime>,
     pub subtitle_groups: Vec<SubtitleGroup>,
     pub resources: Vec<Resource>,
+    // 详情页中资源表被截断的字幕组ID，需通过 AnimeParser::expand_url 补全
+    pub truncated_groups: Vec<i64>,
+}
+
+// 订阅源中的单条资源，尚未关联番剧与字幕组
+#[derive(Debug, Clone)]
+pub struct FeedItem {
+    pub title: String,
+    // 资源详情页，如 Mikan 的 /Home/Episode/{hash}
+    pub page_url: Option<String>,
+    pub magnet_url: Option<String>,
+    pub magnet_hash: Option<String>,
+    pub torrent_url: Option<String>,
+    pub file_size: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub release_date: Option<i64>,
+    // 来源未提供字幕组ID时，按发布组名称关联字幕组
+    pub group_name: Option<String>,
+    // 来源已知的字幕类型，标题中未识别出时使用
+    pub subtitle_type: Option<String>,
+    pub seeders: Option<i64>,
+    pub leechers: Option<i64>,
+}
+
+impl FeedItem {
+    /// 关联到番剧与字幕组后转换为资源记录
+    pub fn into_resource(self, source: &str, mikan_id: i64, subtitle_group_id: i64) -> Resource {
+        use crate::core::text_parser;
+        let now = chrono::Utc::now().timestamp_millis();
+        let parsed = text_parser::parse_release(&self.title);
+        let mut resource = Resource {
+            mikan_id,
+            subtitle_group_id,
+            size_bytes: self.size_bytes.or_else(|| {
+                self.file_size
+                    .as_deref()
+                    .and_then(text_parser::parse_file_size_bytes)
+            }),
+            file_size: self.file_size,
+            magnet_url: self.magnet_url,
+            torrent_url: self.torrent_url,
+            magnet_hash: self.magnet_hash,
+            release_date: self.release_date,
+            created_at: Some(now),
+            updated_at: Some(now),
+            source: source.to_string(),
+            seeders: self.seeders,
+            leechers: self.leechers,
+            title: self.title,
+            ..Default::default()
+        };
+        parsed.apply_to(&mut resource, self.subtitle_type);
+        resource
+    }
 }
@@ -0,0 +1,142 @@
+use crate::core::anime_parser::{AnimeParser, FeedMapping};
+use crate::core::rss::parse_rss;
+use crate::core::text_parser;
+use crate::core::FeedItem;
+use crate::error::Result;
+use crate::types::crawler::CrawlerTaskCreate;
+
+pub const SOURCE_NAME: &str = "nyaa";
+pub const DEFAULT_BASE_URL: &str = "https://nyaa.si";
+// Nyaa "Anime - English-translated" 分类
+const ENGLISH_TRANSLATED_CATEGORY: &str = "1_2";
+
+/// Nyaa：英文字幕组为主的订阅源，条目按标题（含别名）匹配到已入库番剧
+pub struct NyaaParser {
+    pub base_url: String,
+}
+
+impl NyaaParser {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+}
+
+impl AnimeParser for NyaaParser {
+    fn name(&self) -> &str {
+        SOURCE_NAME
+    }
+
+    fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// 英译动画分类RSS，指定keyword时为搜索结果RSS
+    fn feed_url(&self, params: &CrawlerTaskCreate) -> Option<String> {
+        let url = format!("{}/", self.base_url);
+        let mut query = vec![
+            ("page", "rss"),
+            ("c", ENGLISH_TRANSLATED_CATEGORY),
+            ("f", "0"),
+        ];
+        if let Some(keyword) = params.keyword.as_deref().filter(|k| !k.trim().is_empty()) {
+            query.push(("q", keyword.trim()));
+        }
+        reqwest::Url::parse_with_params(&url, &query)
+            .ok()
+            .map(|url| url.to_string())
+    }
+
+    fn feed_mapping(&self) -> FeedMapping {
+        FeedMapping::TitleMatch
+    }
+
+    fn prefers_alias_keyword(&self) -> bool {
+        true
+    }
+
+    fn parse_feed(&self, xml: &str) -> Result<Vec<FeedItem>> {
+        let doc = parse_rss(xml)?;
+        Ok(doc
+            .channel
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                let magnet_hash = item
+                    .info_hash
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|hash| hash.len() == 40)?
+                    .to_lowercase();
+                let subtitle_type = (item.category_id.as_deref()
+                    == Some(ENGLISH_TRANSLATED_CATEGORY))
+                .then(|| "英语".to_string());
+                let size_bytes = item
+                    .size
+                    .as_deref()
+                    .and_then(text_parser::parse_file_size_bytes);
+                Some(FeedItem {
+                    // link 为种子下载地址，guid 为资源页
+                    page_url: item.guid,
+                    magnet_url: Some(format!("magnet:?xt=urn:btih:{}", magnet_hash)),
+                    magnet_hash: Some(magnet_hash),
+                    torrent_url: item.link,
+                    file_size: size_bytes.map(text_parser::format_file_size),
+                    size_bytes,
+                    release_date: item.pub_date.as_deref().and_then(|date| {
+                        text_parser::parse_datetime_to_timestamp(date, self.timezone())
+                    }),
+                    subtitle_type,
+                    seeders: item.seeders,
+                    leechers: item.leechers,
+                    group_name: text_parser::parse_release_group(&item.title),
+                    title: item.title.trim().to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feed_reads_nyaa_extensions() {
+        let parser = NyaaParser::new(DEFAULT_BASE_URL);
+        let items = parser
+            .parse_feed(include_str!("testdata/nyaa_feed.xml"))
+            .unwrap();
+        // 缺少 infoHash 的条目被跳过
+        assert_eq!(items.len(), 2);
+
+        let item = &items[0];
+        assert_eq!(
+            item.magnet_hash.as_deref(),
+            Some("abcdef0123456789abcdef0123456789abcdef01")
+        );
+        assert_eq!(
+            item.magnet_url.as_deref(),
+            Some("magnet:?xt=urn:btih:abcdef0123456789abcdef0123456789abcdef01")
+        );
+        assert_eq!(
+            item.torrent_url.as_deref(),
+            Some("https://nyaa.si/download/1720001.torrent")
+        );
+        assert_eq!(
+            item.page_url.as_deref(),
+            Some("https://nyaa.si/view/1720001")
+        );
+        assert_eq!(item.size_bytes, Some(1_503_238_553));
+        assert_eq!(item.seeders, Some(1523));
+        assert_eq!(item.leechers, Some(42));
+        assert_eq!(item.subtitle_type.as_deref(), Some("英语"));
+        assert_eq!(item.group_name.as_deref(), Some("SubsPlease"));
+        assert_eq!(item.release_date, Some(1_696_604_482_000));
+
+        // 非英译分类不标注字幕类型
+        assert_eq!(items[1].subtitle_type, None);
+        assert_eq!(items[1].seeders, Some(88));
+    }
+}
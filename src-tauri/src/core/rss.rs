@@ -0,0 +1,80 @@
+use crate::error::{AppError, DomainError, Result};
+use serde::Deserialize;
+
+// =============================================================================
+// 通用 RSS 2.0 结构，各资源站的订阅源共用
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RssDocument {
+    pub channel: RssChannel,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RssChannel {
+    #[serde(default, rename = "item")]
+    pub items: Vec<RssItem>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RssItem {
+    pub title: String,
+    pub link: Option<String>,
+    #[serde(rename = "pubDate")]
+    pub pub_date: Option<String>,
+    pub author: Option<String>,
+    pub guid: Option<String>,
+    pub enclosure: Option<RssEnclosure>,
+    // Mikan 扩展：<torrent xmlns="https://mikanani.me/0.1/">
+    pub torrent: Option<MikanTorrentExt>,
+    // Nyaa 扩展：<nyaa:infoHash>、<nyaa:size>、<nyaa:seeders> 等，反序列化时忽略命名空间前缀
+    #[serde(rename = "infoHash")]
+    pub info_hash: Option<String>,
+    pub size: Option<String>,
+    pub seeders: Option<i64>,
+    pub leechers: Option<i64>,
+    #[serde(rename = "categoryId")]
+    pub category_id: Option<String>,
+    // Torznab 扩展：<torznab:attr name="seeders" value="10"/>
+    #[serde(default, rename = "attr")]
+    pub attrs: Vec<TorznabAttr>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RssEnclosure {
+    #[serde(rename = "@url")]
+    pub url: String,
+    #[serde(rename = "@length")]
+    pub length: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MikanTorrentExt {
+    #[serde(rename = "contentLength")]
+    pub content_length: Option<i64>,
+    #[serde(rename = "pubDate")]
+    pub pub_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TorznabAttr {
+    #[serde(rename = "@name")]
+    pub name: String,
+    #[serde(rename = "@value")]
+    pub value: String,
+}
+
+impl RssItem {
+    /// 按名称读取 Torznab 扩展属性
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|attr| attr.name.eq_ignore_ascii_case(name))
+            .map(|attr| attr.value.as_str())
+    }
+}
+
+pub fn parse_rss(xml: &str) -> Result<RssDocument> {
+    quick_xml::de::from_str(xml)
+        .map_err(|e| AppError::Domain(DomainError::Serialization(format!("RSS解析失败: {}", e))))
+}
@@ -0,0 +1,111 @@
+use crate::config::Config;
+use crate::core::anime_parser::AnimeParser;
+use crate::core::dmhy_parser::{self, DmhyParser};
+use crate::core::mikan_parser::{self, MikanParser};
+use crate::core::nyaa_parser::{self, NyaaParser};
+use crate::core::torznab_parser::TorznabParser;
+use crate::error::{AppError, InputError, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+// 任务未指定来源时使用 Mikan
+pub const DEFAULT_SOURCE: &str = mikan_parser::SOURCE_NAME;
+
+struct RegisteredSource {
+    parser: Arc<dyn AnimeParser>,
+    enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceInfo {
+    pub name: String,
+    pub base_url: String,
+    pub enabled: bool,
+}
+
+/// 资源来源注册表，按名称查找解析器，并根据配置启用/禁用
+pub struct SourceRegistry {
+    sources: BTreeMap<String, RegisteredSource>,
+}
+
+impl SourceRegistry {
+    /// 注册所有内置来源，base_url 与启用状态取自配置
+    pub fn from_config(config: &Config) -> Self {
+        let mut registry = Self {
+            sources: BTreeMap::new(),
+        };
+        let mikan = config.source(mikan_parser::SOURCE_NAME);
+        registry.register(
+            Arc::new(MikanParser::new(
+                mikan
+                    .base_url
+                    .as_deref()
+                    .unwrap_or(mikan_parser::DEFAULT_BASE_URL),
+            )),
+            mikan.enabled.unwrap_or(true),
+        );
+        let dmhy = config.source(dmhy_parser::SOURCE_NAME);
+        registry.register(
+            Arc::new(DmhyParser::new(
+                dmhy.base_url
+                    .as_deref()
+                    .unwrap_or(dmhy_parser::DEFAULT_BASE_URL),
+            )),
+            dmhy.enabled.unwrap_or(true),
+        );
+        let nyaa = config.source(nyaa_parser::SOURCE_NAME);
+        registry.register(
+            Arc::new(NyaaParser::new(
+                nyaa.base_url
+                    .as_deref()
+                    .unwrap_or(nyaa_parser::DEFAULT_BASE_URL),
+            )),
+            nyaa.enabled.unwrap_or(true),
+        );
+        for endpoint in config.torznab_endpoints.iter().flatten() {
+            if registry.sources.contains_key(&endpoint.name) {
+                tracing::warn!("Torznab来源{}与已有来源重名，已忽略", endpoint.name);
+                continue;
+            }
+            registry.register(
+                Arc::new(TorznabParser::new(endpoint)),
+                endpoint.enabled.unwrap_or(true),
+            );
+        }
+        registry
+    }
+
+    pub fn register(&mut self, parser: Arc<dyn AnimeParser>, enabled: bool) {
+        self.sources.insert(
+            parser.name().to_string(),
+            RegisteredSource { parser, enabled },
+        );
+    }
+
+    /// 获取已启用的来源，未注册或已禁用时返回错误
+    pub fn get(&self, name: &str) -> Result<Arc<dyn AnimeParser>> {
+        match self.sources.get(name) {
+            Some(source) if source.enabled => Ok(source.parser.clone()),
+            Some(_) => Err(AppError::Input(InputError::Invalid(format!(
+                "资源来源{}已禁用",
+                name
+            )))),
+            None => Err(AppError::Input(InputError::Invalid(format!(
+                "未知的资源来源: {}",
+                name
+            )))),
+        }
+    }
+
+    pub fn list(&self) -> Vec<SourceInfo> {
+        self.sources
+            .iter()
+            .map(|(name, source)| SourceInfo {
+                name: name.clone(),
+                base_url: source.parser.base_url().to_string(),
+                enabled: source.enabled,
+            })
+            .collect()
+    }
+}
@@ -1,202 +1,704 @@
-use chrono::NaiveDateTime;
+use crate::models::{EpisodeKind, Resource, SubtitleDelivery};
+use chrono::{NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashMap;
+use serde::Serialize;
 
 // =============================================================================
-// Episode Number Parsing
+// Release Title Parsing
 // =============================================================================
 
-pub fn parse_episode_number(title: &str) -> Option<i32> {
-    let patterns = [
-        r"第(\d{1,4})[话集]",
-        r"EP(\d{1,3})",
-        r"E(\d{1,3})",
-        r"Episode\s*(\d{1,3})",
-        r"- (\d{1,3})v\d+",
-        r"- (\d{1,3})\s",
-        r"\[(\d{1,3})v\d+\]",
-        r"\[(\d{1,3})\]",
-        r"【(\d{1,3})】",
-    ];
+/// 解析规则的版本，规则变化导致结果不同时递增，重新解析任务据此找出需要更新的资源
+pub const PARSER_VERSION: i32 = 1;
 
-    for pattern in patterns.iter() {
-        let re = Regex::new(pattern).unwrap();
-        for caps in re.captures_iter(title) {
-            if let Some(matched) = caps.get(1) {
-                if let Ok(num) = matched.as_str().parse::<i32>() {
-                    if is_valid_episode_number(title, matched.as_str(), num) {
-                        return Some(num);
-                    }
-                }
+/// 发布标题的结构化解析结果，下游统一基于此结构取集数、分辨率、字幕等信息
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ParsedRelease {
+    pub group: Option<String>,
+    // 标题中的作品名，常见中文、日文、英文/罗马音并列
+    pub titles: Vec<String>,
+    pub season: Option<i32>,
+    // 正片的整数集数，小数集数与特别篇等为 None
+    pub episode: Option<i32>,
+    // 合集的结束集数，单集资源为 None
+    pub episode_end: Option<i32>,
+    pub episode_kind: EpisodeKind,
+    // 剧集类型内的精确编号，如13.5、SP2
+    pub episode_sort: Option<f64>,
+    pub version: Option<i32>,
+    // 标题未写明分辨率时按片源推断
+    pub resolution: Option<String>,
+    // 片源：BDRip / WebRip / WEB-DL 等
+    pub source: Option<String>,
+    // 流媒体平台：Baha / CR / B-Global 等，出现时片源视为 WEB-DL
+    pub platform: Option<String>,
+    pub video_codec: Option<String>,
+    // HDR 格式：HDR10 / HDR10+ / HLG / DV（杜比视界）
+    pub hdr: Option<String>,
+    pub audio_codec: Option<String>,
+    pub bit_depth: Option<i32>,
+    pub container: Option<String>,
+    // 字幕语言：chs / cht / jpn / eng，按此顺序排列
+    pub subtitle_languages: Vec<String>,
+    pub subtitle_delivery: Option<SubtitleDelivery>,
+    // 音轨语言：jpn / zho / yue / eng，多音轨为 multi
+    pub audio_language: Option<String>,
+    // 标题中第一个命中的字幕关键词归一化后的展示标签，如“简繁双语”“中日双语”
+    pub subtitle_type: Option<String>,
+    pub is_batch: bool,
+}
+
+impl ParsedRelease {
+    /// 写入资源中由标题得出的字段，入库与重新解析共用。
+    /// 标题中未识别出字幕类型时使用 fallback_subtitle_type（如订阅源提供的分类），仍没有时记为“其他”
+    pub fn apply_to(self, resource: &mut Resource, fallback_subtitle_type: Option<String>) {
+        resource.episode_number = self.episode;
+        resource.episode_end = self.episode_end;
+        resource.season = self.season;
+        resource.episode_kind = self.episode_kind;
+        resource.episode_sort = self.episode_sort;
+        resource.version = self.version;
+        resource.resolution = self.resolution;
+        resource.subtitle_type = Some(
+            self.subtitle_type
+                .or(fallback_subtitle_type)
+                .unwrap_or_else(|| "其他".to_string()),
+        );
+        resource.subtitle_languages =
+            (!self.subtitle_languages.is_empty()).then(|| self.subtitle_languages.join(","));
+        resource.subtitle_delivery = self.subtitle_delivery;
+        resource.audio_language = self.audio_language;
+        resource.video_codec = self.video_codec;
+        resource.bit_depth = self.bit_depth;
+        resource.hdr = self.hdr;
+        resource.audio_codec = self.audio_codec;
+        resource.release_source = self.source;
+        resource.platform = self.platform;
+        resource.container = self.container;
+        resource.parser_version = Some(PARSER_VERSION);
+    }
+}
+
+type TokenTable = Lazy<Vec<(Regex, &'static str)>>;
+
+fn compile_table(entries: &[(&str, &'static str)]) -> Vec<(Regex, &'static str)> {
+    entries
+        .iter()
+        .map(|(pattern, value)| (Regex::new(pattern).unwrap(), *value))
+        .collect()
+}
+
+fn compile_all(patterns: &[&str]) -> Vec<Regex> {
+    patterns.iter().map(|p| Regex::new(p).unwrap()).collect()
+}
+
+// [] 【】 为发布组/元数据块，() （）只作为元数据，不会当作标题
+static BRACKET_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"\[([^\[\]]*)\]|【([^【】]*)】|\(([^()]*)\)|（([^（）]*)）").unwrap());
+static ANNOUNCE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"★[^★]*★").unwrap());
+static WORD_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\s_/|]+").unwrap());
+// 宣传、地区限制等方括号块，不含作品名
+static NOISE_SEGMENT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"新番|月番|招募|字幕组|检索|搬运|仅限|僅限|港澳台").unwrap());
+
+static BRACKET_EPISODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:第|EP?\s*)?(?P<ep>\d{1,4}(?:\.\d)?)(?:\s*[话話集])?(?:v(?P<ver>\d{1,2}))?(?:\s*(?:END|Fin|完))?$")
+        .unwrap()
+});
+// 合集范围："第01-12话"、"- 01-12"、"BDRip 1-24"、"01-12Fin"、"全12话"（起始集数缺省为1）
+static RANGE_RES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    compile_all(&[
+        r"第\s*(?P<start>\d{1,4})\s*[-~～]\s*(?P<end>\d{1,4})\s*[话話集]",
+        r"(?:^|\s)-\s*(?P<start>\d{1,4})\s*[-~～]\s*(?P<end>\d{1,4})(?:\s|$)",
+        r"(?i)(?:^|\s)(?P<start>\d{1,3})(?:-|\s*[~～]\s*)(?P<end>\d{1,3})\s*(?:TV|BD)?\s*(?:fin|end|完|合集|全集)?(?:\s|$)",
+        r"全\s*(?P<end>\d{1,4})\s*[话話集]",
+    ])
+});
+static TEXT_EPISODE_RES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    compile_all(&[
+        r"(?i)\bS\d{1,2}E(?P<ep>\d{1,4})(?:v(?P<ver>\d{1,2}))?\b",
+        r"第\s*(?P<ep>\d{1,4}(?:\.\d)?)\s*[话話集](?:\s*v(?P<ver>\d{1,2}))?",
+        r"(?i)\b(?:EP?|Episode\s*)(?P<ep>\d{1,4}(?:\.\d)?)(?:v(?P<ver>\d{1,2}))?\b",
+        r"(?:^|\s)-\s*(?P<ep>\d{1,4}(?:\.\d)?)(?:v(?P<ver>\d{1,2}))?(?:\s|$)",
+    ])
+});
+// 特别篇、OP/ED 与总集篇，可带编号如 "SP01"、"OVA 2"、"NCOP1"；
+// SP/OP/ED 只认大写，避免 "Ed Edd n Eddy" 这类英文单词被当成特别篇
+static SPECIAL_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"\b(?P<kind>SPs?|NCOP|NCED|OP|ED|(?i:Specials?|OVA|OAD|OAV))\s*(?P<num>\d{1,3}(?:\.\d)?)?\b",
+        r"|(?P<recap>总集篇|總集篇|特别篇|特別篇|番外篇?)",
+    ))
+    .unwrap()
+});
+static MOVIE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)剧场版|劇場版|\bthe movie\b|\bmovie\b").unwrap());
+// 季度："S2"、"S01E05"、"Season 2"、"2nd Season"、"Part 2"、"第二季"、"第2期"
+static SEASON_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?i)\bS(?P<s1>\d{1,2})(?:E\d{1,4}(?:v\d{1,2})?)?\b",
+        r"|\bSeason\s*(?P<s2>\d{1,2})\b",
+        r"|\b(?P<s3>\d{1,2})(?:st|nd|rd|th)\s+Season\b",
+        r"|\bPart\s*(?P<s4>\d{1,2})\b",
+        r"|第\s*(?P<s5>\d{1,2}|[一二三四五六七八九十]{1,3})\s*[季期]",
+    ))
+    .unwrap()
+});
+static BATCH_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)合集|全集|\bbatch\b|\bcomplete\b|全\s*\d+\s*[话話集]").unwrap());
+
+static RESOLUTION_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:(?P<p>\d{3,4})p|\d{3,4}[x×](?P<h>\d{3,4})|(?P<k>[248])k)$").unwrap()
+});
+static BIT_DEPTH_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^(8|10|12)-?bits?$").unwrap());
+// Hi10P 为 10bit AVC，Ma10p / Main10 为 10bit HEVC
+static PROFILE_10BIT_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(?:(?P<avc>hi)10p|ma10p|main-?10)$").unwrap());
+static VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^v(\d{1,2})$").unwrap());
+static LANGUAGE_WORD_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[简繁中日英粤体文语双多字幕内封嵌外挂]{2,}$").unwrap());
+// 语言代码可连写，如 JPSC、CHS_JP
+static LANGUAGE_CODES_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^(?:CHS|CHT|BIG5|GB|SC|TC|JPN|JAP|JP|ENG|EN)+$").unwrap());
+static LANGUAGE_CODE_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)(?P<chs>CHS|GB|SC)|(?P<cht>CHT|BIG5|TC)|(?P<jpn>JPN|JAP|JP)|(?P<eng>ENG|EN)")
+        .unwrap()
+});
+// 识别但不记录的标记
+static NOISE_WORD_RE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:ASS(?:x\d)?|SRT|PGS|SUP|RAW|END|Fin|完|无字幕|\d{2,3}fps)$").unwrap()
+});
+
+static AUDIO_LANGUAGE_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"^(?:日语原声|日配|原声)$", "jpn"),
+        (r"^(?:国语|国配|中配|国语配音|普通话)$", "zho"),
+        (r"^(?:粤语|粤配)$", "yue"),
+        (r"^(?:英配|英语配音)$", "eng"),
+        (
+            r"(?i)^(?:双语音轨|双音轨|多音轨|国日双语|Dual-?Audio|Multi-?Audio)$",
+            "multi",
+        ),
+    ])
+});
+
+static SOURCE_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^BD-?Rip$", "BDRip"),
+        (r"(?i)^(?:BD|Blu-?Ray|BDMV|BD-?Remux)$", "BluRay"),
+        (r"(?i)^WEB-?Rip$", "WebRip"),
+        (r"(?i)^(?:WEB-?DL|WEB)$", "WEB-DL"),
+        (r"(?i)^HDTV(?:-?Rip)?$", "HDTV"),
+        (r"(?i)^DVD-?Rip$", "DVDRip"),
+        (r"(?i)^DVD$", "DVD"),
+        (r"(?i)^TV-?Rip$", "TVRip"),
+    ])
+});
+static PLATFORM_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^Baha(?:mut)?$", "Baha"),
+        (r"(?i)^(?:CR|Crunchyroll)$", "CR"),
+        (r"(?i)^(?:B-?Global|Bilibili|Bstation)$", "B-Global"),
+        (r"(?i)^(?:NF|Netflix)$", "Netflix"),
+        (r"(?i)^(?:AMZN|Amazon)$", "Amazon"),
+        (r"(?i)^(?:DSNP|Disney\+)$", "Disney+"),
+        (r"(?i)^ABEMA$", "ABEMA"),
+        (r"(?i)^U-?NEXT$", "U-NEXT"),
+        (r"(?i)^iQIYI$", "iQIYI"),
+        (r"(?i)^Hulu$", "Hulu"),
+    ])
+});
+static HDR_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^(?:DV|DoVi|Dolby-?Vision)$", "DV"),
+        (r"(?i)^HDR10(?:\+|Plus)$", "HDR10+"),
+        (r"(?i)^HDR(?:10)?$", "HDR10"),
+        (r"(?i)^HLG$", "HLG"),
+    ])
+});
+static VIDEO_CODEC_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^(?:[hx]\.?265|HEVC)$", "HEVC"),
+        (r"(?i)^(?:[hx]\.?264|AVC)$", "AVC"),
+        (r"(?i)^AV1$", "AV1"),
+        (r"(?i)^VP9$", "VP9"),
+    ])
+});
+static AUDIO_CODEC_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^AAC(?:x\d)?$", "AAC"),
+        (r"(?i)^FLAC(?:x\d)?$", "FLAC"),
+        (r"(?i)^OPUS(?:x\d)?$", "Opus"),
+        (r"(?i)^(?:E-?AC-?3|DDP)$", "EAC3"),
+        (r"(?i)^(?:AC-?3|DD)$", "AC3"),
+        (r"(?i)^DTS(?:-?HD)?(?:-?MA)?$", "DTS"),
+        (r"(?i)^TrueHD$", "TrueHD"),
+        (r"(?i)^MP3$", "MP3"),
+    ])
+});
+static CONTAINER_TABLE: TokenTable = Lazy::new(|| {
+    compile_table(&[
+        (r"(?i)^MKV$", "MKV"),
+        (r"(?i)^MP4$", "MP4"),
+        (r"(?i)^AVI$", "AVI"),
+    ])
+});
+
+/// 解析发布标题，如 "[LoliHouse] 葬送的芙莉莲 / Sousou no Frieren - 01 [WebRip 1080p HEVC-10bit AAC][简繁内封字幕]"
+pub fn parse_release(title: &str) -> ParsedRelease {
+    let mut parsed = ParsedRelease::default();
+    let mut last_end = 0;
+    let mut first = true;
+
+    for caps in BRACKET_RE.captures_iter(title) {
+        let whole = caps.get(0).unwrap();
+        parse_text_segment(&title[last_end..whole.start()], &mut parsed);
+        last_end = whole.end();
+
+        let is_paren = caps.get(3).is_some() || caps.get(4).is_some();
+        let content = (1..=4)
+            .find_map(|i| caps.get(i))
+            .map_or("", |m| m.as_str())
+            .trim();
+        // 标题开头的方括号为发布组
+        if first && !is_paren && title[..whole.start()].trim().is_empty() && !content.is_empty() {
+            parsed.group = Some(content.to_string());
+        } else {
+            parse_bracket_segment(content, is_paren, &mut parsed);
+        }
+        first = false;
+    }
+    parse_text_segment(&title[last_end..], &mut parsed);
+
+    match parsed.episode_kind {
+        EpisodeKind::Main => {}
+        // 剧场版在 Bangumi 上为单集条目
+        EpisodeKind::Movie => {
+            if parsed.episode_sort.is_none() {
+                parsed.episode = Some(1);
+                parsed.episode_sort = Some(1.0);
             }
         }
+        _ => {
+            parsed.episode = None;
+            parsed.episode_end = None;
+            parsed.episode_sort.get_or_insert(1.0);
+        }
     }
-    None
+    if parsed.source.is_none() && parsed.platform.is_some() {
+        parsed.source = Some("WEB-DL".to_string());
+    }
+    if parsed.resolution.is_none() {
+        parsed.resolution = parsed
+            .source
+            .as_deref()
+            .and_then(infer_resolution_from_source)
+            .map(str::to_string);
+    }
+    // 展示用的字幕类型沿用关键词归一化，筛选使用结构化的语言与交付方式字段
+    parsed.subtitle_type = SUBTITLE_KEYWORDS
+        .iter()
+        .find(|keyword| title.contains(*keyword))
+        .map(|keyword| normalize_subtitle_type(keyword));
+    parsed
 }
 
-fn is_valid_episode_number(_title: &str, _matched_str: &str, episode_num: i32) -> bool {
-    !(2000..=2030).contains(&episode_num)
+fn parse_bracket_segment(content: &str, is_paren: bool, parsed: &mut ParsedRelease) {
+    if content.is_empty() {
+        return;
+    }
+    if let Some(caps) = BRACKET_EPISODE_RE.captures(content) {
+        // 不合理的数字（如年份）直接忽略
+        set_episode(parsed, &caps["ep"], caps.name("ver").map(|m| m.as_str()));
+        return;
+    }
+    if BATCH_RE.is_match(content) {
+        parsed.is_batch = true;
+    }
+
+    // 范围与片源等写在同一块中，如 "[BDRip 1-24 1080p]"
+    let mut recognized = find_range(content, parsed).is_some();
+    recognized |= find_special(content, parsed).is_some();
+    for word in WORD_RE.find_iter(content) {
+        recognized |= classify_word(word.as_str(), parsed);
+    }
+    // 形如 "[葬送的芙莉莲 / Sousou no Frieren]" 的作品名块，只会出现在集数之前
+    if !recognized
+        && !is_paren
+        && parsed.episode_sort.is_none()
+        && !NOISE_SEGMENT_RE.is_match(content)
+    {
+        parse_text_segment(content, parsed);
+    }
 }
 
-// =============================================================================
-// Resolution Parsing
-// =============================================================================
+/// 解析方括号之外的文本：集数、季度之前的部分为作品名，之后为技术参数
+fn parse_text_segment(text: &str, parsed: &mut ParsedRelease) {
+    let text = ANNOUNCE_RE.replace_all(text, " ");
+    let mut text = text.trim().to_string();
+    if text.is_empty() {
+        return;
+    }
+    // 形如 "Title.S01E01.1080p.WEB-DL" 的点分隔标题
+    if !text.contains(char::is_whitespace) && text.matches('.').count() >= 2 {
+        text = text.replace('.', " ");
+    }
 
-pub fn parse_resolution(title: &str) -> Option<String> {
-    if let Some(res) = from_resolution_str(title) {
-        return Some(res);
+    let mut cut = find_range(&text, parsed).unwrap_or(text.len());
+    for re in TEXT_EPISODE_RES.iter() {
+        if let Some(caps) = re.captures(&text) {
+            if set_episode(parsed, &caps["ep"], caps.name("ver").map(|m| m.as_str())) {
+                cut = cut.min(caps.get(0).unwrap().start());
+                break;
+            }
+        }
     }
-    if let Some(res) = from_dimensions(title) {
-        return Some(res);
+    if let Some(caps) = SEASON_RE.captures(&text) {
+        let season = ["s1", "s2", "s3", "s4", "s5"]
+            .iter()
+            .find_map(|name| caps.name(name))
+            .and_then(|m| parse_number(m.as_str()));
+        if parsed.season.is_none() {
+            parsed.season = season;
+        }
     }
-    if let Some(res) = infer_from_source(title) {
-        return Some(res);
+    if let Some(start) = find_special(&text, parsed) {
+        cut = cut.min(start);
+    }
+    if MOVIE_RE.is_match(&text) && parsed.episode_kind == EpisodeKind::Main {
+        parsed.episode_kind = EpisodeKind::Movie;
+    }
+    if let Some(m) = BATCH_RE.find(&text) {
+        parsed.is_batch = true;
+        cut = cut.min(m.start());
+    }
+    for word in WORD_RE.find_iter(&text) {
+        if classify_word(word.as_str(), parsed) {
+            cut = cut.min(word.start());
+        }
     }
-    None
-}
 
-fn from_resolution_str(title: &str) -> Option<String> {
-    let re = Regex::new(r"(\d{3,4}[pP])").unwrap();
-    re.captures(title).map(|caps| caps[1].to_lowercase())
+    // 季度与剧场版标记不属于作品名，如 "间谍过家家 第二季 / SPY×FAMILY Season 2"
+    for title in text[..cut].split(['/', '|']) {
+        let title = SEASON_RE.replace_all(title, "");
+        let title = MOVIE_RE.replace_all(&title, "");
+        let title = title.trim_matches(|c: char| c.is_whitespace() || c == '-' || c == '_');
+        if !title.is_empty() && !parsed.titles.iter().any(|t| t == title) {
+            parsed.titles.push(title.to_string());
+        }
+    }
 }
 
-fn from_dimensions(title: &str) -> Option<String> {
-    let re = Regex::new(r"(\d{3,4})[xX](\d{3,4})").unwrap();
-    re.captures(title).and_then(|caps| {
-        let h = caps[2].parse::<i32>().ok()?;
-        Some(match h {
-            h if h >= 2100 => "2160p".to_string(),
-            h if h >= 1070 => "1080p".to_string(),
-            h if h >= 700 => "720p".to_string(),
-            _ => "480p".to_string(),
-        })
-    })
+/// 识别单个标记；"HEVC-10bit"、"CHS&CHT" 等连写的标记拆开再识别
+fn classify_word(word: &str, parsed: &mut ParsedRelease) -> bool {
+    if classify_token(word, parsed) {
+        return true;
+    }
+    let mut recognized = false;
+    for part in word.split(['-', '.', '+', '&']).filter(|p| !p.is_empty()) {
+        recognized |= classify_token(part, parsed);
+    }
+    recognized
 }
 
-fn infer_from_source(title: &str) -> Option<String> {
-    let title_upper = title.to_uppercase();
-    let source_map = [
-        ("BDRIP", "1080p"),
-        ("BLURAY", "1080p"),
-        ("BD", "1080p"),
-        ("WEBRIP", "1080p"),
-        ("WEB-DL", "1080p"),
-        ("WEBDL", "1080p"),
-        ("HDTV", "720p"),
-        ("HDTVRIP", "720p"),
-        ("DVDRIP", "480p"),
-        ("DVD", "480p"),
+fn classify_token(token: &str, parsed: &mut ParsedRelease) -> bool {
+    if let Some(resolution) = match_resolution(token) {
+        parsed.resolution.get_or_insert(resolution);
+        return true;
+    }
+    if let Some(caps) = BIT_DEPTH_RE.captures(token) {
+        parsed.bit_depth = parsed.bit_depth.or(caps[1].parse().ok());
+        return true;
+    }
+    if let Some(caps) = PROFILE_10BIT_RE.captures(token) {
+        parsed.bit_depth.get_or_insert(10);
+        let codec = if caps.name("avc").is_some() {
+            "AVC"
+        } else {
+            "HEVC"
+        };
+        parsed.video_codec.get_or_insert_with(|| codec.to_string());
+        return true;
+    }
+    if let Some(caps) = VERSION_RE.captures(token) {
+        parsed.version = parsed.version.or(caps[1].parse().ok());
+        return true;
+    }
+
+    let tables = [
+        (&*SOURCE_TABLE, &mut parsed.source),
+        (&*PLATFORM_TABLE, &mut parsed.platform),
+        (&*VIDEO_CODEC_TABLE, &mut parsed.video_codec),
+        (&*HDR_TABLE, &mut parsed.hdr),
+        (&*AUDIO_CODEC_TABLE, &mut parsed.audio_codec),
+        (&*CONTAINER_TABLE, &mut parsed.container),
+        (&*AUDIO_LANGUAGE_TABLE, &mut parsed.audio_language),
     ];
-    for (source, resolution) in source_map.iter() {
-        if title_upper.contains(source) {
-            return Some(resolution.to_string());
+    for (table, field) in tables {
+        if let Some((_, value)) = table.iter().find(|(re, _)| re.is_match(token)) {
+            field.get_or_insert_with(|| value.to_string());
+            return true;
         }
     }
-    None
+
+    if let Some((languages, delivery)) = match_subtitle(token) {
+        for language in languages {
+            if !parsed.subtitle_languages.iter().any(|l| l == language) {
+                parsed.subtitle_languages.push(language.to_string());
+            }
+        }
+        parsed
+            .subtitle_languages
+            .sort_by_key(|l| SUBTITLE_LANGUAGES.iter().position(|code| code == l));
+        parsed.subtitle_delivery = parsed.subtitle_delivery.or(delivery);
+        return true;
+    }
+    NOISE_WORD_RE.is_match(token)
 }
 
-// =============================================================================
-// Subtitle Type Parsing & Normalization
-// =============================================================================
+fn match_resolution(token: &str) -> Option<String> {
+    let caps = RESOLUTION_RE.captures(token)?;
+    if let Some(p) = caps.name("p") {
+        return Some(format!("{}p", p.as_str()));
+    }
+    if let Some(k) = caps.name("k") {
+        return Some(
+            match k.as_str() {
+                "8" => "4320p",
+                "4" => "2160p",
+                _ => "1440p",
+            }
+            .to_string(),
+        );
+    }
+    let h = caps.name("h")?.as_str().parse::<i32>().ok()?;
+    Some(
+        match h {
+            h if h >= 2100 => "2160p",
+            h if h >= 1070 => "1080p",
+            h if h >= 700 => "720p",
+            _ => "480p",
+        }
+        .to_string(),
+    )
+}
 
-pub fn parse_and_normalize_subtitle_type(title: &str) -> Option<String> {
-    let keywords = [
-        "简繁日内封",
-        "简繁日内嵌",
-        "简繁日多语",
-        "简繁英",
-        "简体日语双语",
-        "繁体日语双语",
-        "简日双语",
-        "简日双字",
-        "繁日双语",
-        "繁日双字",
-        "中日双语",
-        "中日双字",
-        "简繁双语",
-        "简繁双字",
-        "双语字幕",
-        "简日",
-        "繁日",
-        "简英",
-        "繁英",
-        "简繁",
-        "简日内封",
-        "繁日内封",
-        "简日内嵌",
-        "繁日内嵌",
-        "简繁内封",
-        "简繁内挂",
-        "简繁内嵌",
-        "简体内封",
-        "简体内挂",
-        "简体内嵌",
-        "繁体内封",
-        "繁体内挂",
-        "繁体内嵌",
-        "简体外挂",
-        "繁体外挂",
-        "简繁外挂",
-        "外挂字幕",
-        "CHT",
-        "CHS",
-        "GB",
-        "BIG5",
-        "简体",
-        "繁体",
-        "简中",
-        "繁中",
-        "中字",
-        "英语",
-        "内嵌字幕",
-        "内挂字幕",
-        "中文字幕",
-        "日语原声",
-        "无字幕",
-        "RAW",
-    ];
+/// 解析字幕语言与交付方式，如 "简繁日内封"、"CHS"、"JPSC"
+fn match_subtitle(token: &str) -> Option<(Vec<&'static str>, Option<SubtitleDelivery>)> {
+    if LANGUAGE_CODES_RE.is_match(token) {
+        let languages = LANGUAGE_CODE_RE
+            .captures_iter(token)
+            .filter_map(|caps| {
+                SUBTITLE_LANGUAGES
+                    .iter()
+                    .copied()
+                    .find(|code| caps.name(code).is_some())
+            })
+            .collect();
+        return Some((languages, None));
+    }
+    if !LANGUAGE_WORD_RE.is_match(token) {
+        return None;
+    }
+    let mut languages = Vec::new();
+    if token.contains('简') || (token.contains('中') && !token.contains('繁')) {
+        languages.push("chs");
+    }
+    if token.contains('繁') {
+        languages.push("cht");
+    }
+    if token.contains('日') {
+        languages.push("jpn");
+    }
+    if token.contains('英') {
+        languages.push("eng");
+    }
+    let delivery = if token.contains("内封") || token.contains("内挂") {
+        Some(SubtitleDelivery::Soft)
+    } else if token.contains("内嵌") {
+        Some(SubtitleDelivery::Hard)
+    } else if token.contains("外挂") {
+        Some(SubtitleDelivery::External)
+    } else {
+        None
+    };
+    Some((languages, delivery))
+}
 
-    for keyword in keywords.iter() {
-        if title.contains(keyword) {
-            return Some(normalize_subtitle_type(keyword));
+/// 查找合集范围，返回匹配位置
+fn find_range(text: &str, parsed: &mut ParsedRelease) -> Option<usize> {
+    RANGE_RES.iter().find_map(|re| {
+        let caps = re.captures(text)?;
+        let start = caps.name("start").map_or("1", |m| m.as_str());
+        if !set_range(parsed, start, &caps["end"]) {
+            return None;
         }
+        Some(caps.get(0).unwrap().start())
+    })
+}
+
+fn set_episode(parsed: &mut ParsedRelease, episode: &str, version: Option<&str>) -> bool {
+    let Some(sort) = episode
+        .parse::<f64>()
+        .ok()
+        .filter(|n| is_valid_episode_number(n.trunc() as i32))
+    else {
+        return false;
+    };
+    if parsed.episode_sort.is_none() {
+        parsed.episode_sort = Some(sort);
+        parsed.episode = (sort.fract() == 0.0).then_some(sort as i32);
+        parsed.version = parsed.version.or(version.and_then(|v| v.parse().ok()));
+    }
+    true
+}
+
+fn set_range(parsed: &mut ParsedRelease, start: &str, end: &str) -> bool {
+    let (Ok(start), Ok(end)) = (start.parse::<i32>(), end.parse::<i32>()) else {
+        return false;
+    };
+    if start >= end || !is_valid_episode_number(start) || !is_valid_episode_number(end) {
+        return false;
+    }
+    if parsed.episode_sort.is_none() {
+        parsed.episode = Some(start);
+        parsed.episode_end = Some(end);
+        parsed.episode_sort = Some(start as f64);
+        parsed.is_batch = true;
+    }
+    true
+}
+
+/// 识别特别篇、OP/ED 标记，返回匹配位置
+fn find_special(text: &str, parsed: &mut ParsedRelease) -> Option<usize> {
+    let caps = SPECIAL_RE.captures(text)?;
+    let kind = match caps.name("kind").map(|m| m.as_str().to_ascii_uppercase()) {
+        Some(k) if k.ends_with("OP") => EpisodeKind::Op,
+        Some(k) if k.ends_with("ED") => EpisodeKind::Ed,
+        _ => EpisodeKind::Special,
+    };
+    if parsed.episode_kind == EpisodeKind::Main {
+        parsed.episode_kind = kind;
+    }
+    if let Some(num) = caps.name("num").and_then(|m| m.as_str().parse().ok()) {
+        parsed.episode_sort.get_or_insert(num);
+    }
+    Some(caps.get(0).unwrap().start())
+}
+
+/// 解析阿拉伯数字或一到九十九的中文数字
+fn parse_number(text: &str) -> Option<i32> {
+    if let Ok(n) = text.parse() {
+        return Some(n);
+    }
+    let digit = |c: char| "一二三四五六七八九".find(c).map(|i| i as i32 / 3 + 1);
+    let chars: Vec<char> = text.chars().collect();
+    match chars.as_slice() {
+        ['十'] => Some(10),
+        ['十', d] => Some(10 + digit(*d)?),
+        [t, '十'] => Some(digit(*t)? * 10),
+        [t, '十', d] => Some(digit(*t)? * 10 + digit(*d)?),
+        [d] => digit(*d),
+        _ => None,
+    }
+}
+
+fn is_valid_episode_number(episode_num: i32) -> bool {
+    !(2000..=2030).contains(&episode_num)
+}
+
+fn infer_resolution_from_source(source: &str) -> Option<&'static str> {
+    match source {
+        "BDRip" | "BluRay" | "WebRip" | "WEB-DL" => Some("1080p"),
+        "HDTV" => Some("720p"),
+        "DVDRip" | "DVD" => Some("480p"),
+        _ => None,
     }
-    Some("其他".to_string())
 }
 
+// =============================================================================
+// Subtitle Type Normalization
+// =============================================================================
+
+// 字幕语言代码，按此顺序排列
+const SUBTITLE_LANGUAGES: &[&str] = &["chs", "cht", "jpn", "eng"];
+
+// 按顺序取标题中第一个命中的关键词作为字幕类型
+const SUBTITLE_KEYWORDS: &[&str] = &[
+    "简繁日内封",
+    "简繁日内嵌",
+    "简繁日多语",
+    "简繁英",
+    "简体日语双语",
+    "繁体日语双语",
+    "简日双语",
+    "简日双字",
+    "繁日双语",
+    "繁日双字",
+    "中日双语",
+    "中日双字",
+    "简繁双语",
+    "简繁双字",
+    "双语字幕",
+    "简日",
+    "繁日",
+    "简英",
+    "繁英",
+    "简繁",
+    "简日内封",
+    "繁日内封",
+    "简日内嵌",
+    "繁日内嵌",
+    "简繁内封",
+    "简繁内挂",
+    "简繁内嵌",
+    "简体内封",
+    "简体内挂",
+    "简体内嵌",
+    "繁体内封",
+    "繁体内挂",
+    "繁体内嵌",
+    "简体外挂",
+    "繁体外挂",
+    "简繁外挂",
+    "外挂字幕",
+    "CHT",
+    "CHS",
+    "GB",
+    "BIG5",
+    "简体",
+    "繁体",
+    "简中",
+    "繁中",
+    "中字",
+    "英语",
+    "内嵌字幕",
+    "内挂字幕",
+    "中文字幕",
+    "日语原声",
+    "无字幕",
+    "RAW",
+];
+
 fn normalize_subtitle_type(raw_type: &str) -> String {
-    let mut map = HashMap::new();
-    // 中日双语
-    map.insert("简日双语", "中日双语");
-    map.insert("繁日双语", "中日双语");
-    map.insert("中日双语", "中日双语");
-    map.insert("简日", "中日双语");
-    // 简繁双语
-    map.insert("简繁", "简繁双语");
-    map.insert("简繁双语", "简繁双语");
-    // 简体中文
-    map.insert("CHS", "简体中文");
-    map.insert("简体", "简体中文");
-    map.insert("简中", "简体中文");
-    map.insert("GB", "简体中文");
-    // 繁体中文
-    map.insert("CHT", "繁体中文");
-    map.insert("繁体", "繁体中文");
-    map.insert("繁中", "繁体中文");
-    map.insert("BIG5", "繁体中文");
-    // 无字幕
-    map.insert("无字幕", "无字幕");
-    map.insert("RAW", "无字幕");
-
-    map.get(raw_type)
-        .map_or_else(|| raw_type.to_string(), |s| s.to_string())
+    match raw_type {
+        "简日双语" | "繁日双语" | "中日双语" | "简日" => "中日双语",
+        "简繁" | "简繁双语" => "简繁双语",
+        "CHS" | "简体" | "简中" | "GB" => "简体中文",
+        "CHT" | "繁体" | "繁中" | "BIG5" => "繁体中文",
+        "无字幕" | "RAW" => "无字幕",
+        other => other,
+    }
+    .to_string()
 }
 
 // =============================================================================
 // Datetime Parsing
 // =============================================================================
 
-pub fn parse_datetime_to_timestamp(date_str: &str) -> Option<i64> {
+/// 解析发布时间为UTC毫秒时间戳，未带时区的时间按来源所在时区 tz 解析
+pub fn parse_datetime_to_timestamp(date_str: &str, tz: Tz) -> Option<i64> {
     let formats = [
         "%Y/%m/%d %H:%M",
         "%Y-%m-%dT%H:%M:%S%.f",
@@ -205,13 +707,403 @@ pub fn parse_datetime_to_timestamp(date_str: &str) -> Option<i64> {
     ];
     let date_str = date_str.trim();
 
+    // RSS 的 pubDate 为 RFC 2822 格式，自带时区
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc2822(date_str) {
+        return Some(dt.timestamp_millis());
+    }
+
     for fmt in formats.iter() {
-        if let Ok(dt) = NaiveDateTime::parse_from_str(date_str, fmt) {
-            return Some(dt.and_utc().timestamp_millis());
-        }
-        if let Ok(d) = chrono::NaiveDate::parse_from_str(date_str, fmt) {
-            return Some(d.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp_millis());
+        let local = NaiveDateTime::parse_from_str(date_str, fmt)
+            .ok()
+            .or_else(|| {
+                chrono::NaiveDate::parse_from_str(date_str, fmt)
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+            });
+        if let Some(local) = local {
+            // 夏令时切换造成的重复时刻取较早者
+            return tz
+                .from_local_datetime(&local)
+                .earliest()
+                .map(|dt| dt.timestamp_millis());
         }
     }
     None
 }
+
+/// 解析放送日期，兼容 "2023/4/9"、"2023-04-09"、"4/9/2023" 等写法
+pub fn parse_air_date(date_str: &str) -> Option<chrono::NaiveDate> {
+    let date_str = date_str.trim();
+    ["%Y/%m/%d", "%Y-%m-%d", "%m/%d/%Y", "%Y年%m月%d日"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDate::parse_from_str(date_str, fmt).ok())
+}
+
+// =============================================================================
+// File Size Formatting
+// =============================================================================
+
+/// 将字节数格式化为与 Mikan 页面一致的展示字符串，如 "1.2GB"、"350.5MB"
+pub fn format_file_size(bytes: i64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    const GB: f64 = MB * 1024.0;
+    let bytes = bytes as f64;
+    if bytes >= GB {
+        format!("{:.1}GB", bytes / GB)
+    } else {
+        format!("{:.1}MB", bytes / MB)
+    }
+}
+
+static FILE_SIZE_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?i)^\s*([\d.]+)\s*(?:([KMGT]?)i?B)?\s*$").unwrap());
+
+/// 解析 "1.4 GiB"、"350.2 MiB"、"1.2GB"、"123456"（字节）等文件大小为字节数
+pub fn parse_file_size_bytes(size: &str) -> Option<i64> {
+    let caps = FILE_SIZE_RE.captures(size)?;
+    let value: f64 = caps[1].parse().ok()?;
+    let unit = caps.get(2).map_or("", |m| m.as_str()).to_ascii_uppercase();
+    let exp = match unit.as_str() {
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        _ => 4,
+    };
+    Some((value * 1024f64.powi(exp)) as i64)
+}
+
+// =============================================================================
+// Magnet Hash Parsing
+// =============================================================================
+
+/// 从磁力链接中提取 info hash，统一为40位小写十六进制；兼容32位 base32 编码
+pub fn parse_magnet_hash(magnet_url: &str) -> Option<String> {
+    let start = magnet_url.find("btih:")? + "btih:".len();
+    let hash = magnet_url[start..].split('&').next()?;
+    match hash.len() {
+        40 if hash.chars().all(|c| c.is_ascii_hexdigit()) => Some(hash.to_lowercase()),
+        32 => base32_to_hex(hash),
+        _ => None,
+    }
+}
+
+fn base32_to_hex(input: &str) -> Option<String> {
+    let mut bits: u64 = 0;
+    let mut bit_count = 0;
+    let mut hex = String::with_capacity(40);
+    for c in input.chars() {
+        let value = match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => c as u64 - 'A' as u64,
+            c @ '2'..='7' => c as u64 - '2' as u64 + 26,
+            _ => return None,
+        };
+        bits = (bits << 5) | value;
+        bit_count += 5;
+        while bit_count >= 4 {
+            bit_count -= 4;
+            hex.push_str(&format!("{:x}", (bits >> bit_count) & 0xf));
+        }
+    }
+    Some(hex)
+}
+
+// =============================================================================
+// Release Group Parsing
+// =============================================================================
+
+/// 提取标题开头方括号内的发布组名称，如 "[LoliHouse] ..." 或 "【喵萌奶茶屋】..."
+pub fn parse_release_group(title: &str) -> Option<String> {
+    let title = title.trim_start();
+    let (open, close) = if title.starts_with('[') {
+        ('[', ']')
+    } else if title.starts_with('【') {
+        ('【', '】')
+    } else {
+        return None;
+    };
+    let end = title.find(close)?;
+    let group = title[open.len_utf8()..end].trim();
+    if group.is_empty() {
+        None
+    } else {
+        Some(group.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Map, Value};
+
+    // 标注语料：每条只校验 expected 中列出的字段，字段名与 ParsedRelease 一致
+    #[derive(serde::Deserialize)]
+    struct CorpusCase {
+        title: String,
+        expected: Map<String, Value>,
+    }
+
+    #[test]
+    fn release_corpus() {
+        let cases: Vec<CorpusCase> =
+            serde_json::from_str(include_str!("testdata/release_titles.json")).unwrap();
+        let mut failures = Vec::new();
+        for case in &cases {
+            let parsed = serde_json::to_value(parse_release(&case.title)).unwrap();
+            for (field, expected) in &case.expected {
+                let actual = parsed
+                    .get(field)
+                    .unwrap_or_else(|| panic!("语料中的字段不存在: {}", field));
+                if actual != expected {
+                    failures.push(format!(
+                        "{}\n  {}: 期望 {}，实际 {}",
+                        case.title, field, expected, actual
+                    ));
+                }
+            }
+        }
+        assert!(
+            failures.is_empty(),
+            "{}/{} 条标题解析不符：\n{}",
+            failures.len(),
+            cases.len(),
+            failures.join("\n")
+        );
+    }
+
+    #[test]
+    fn release_fields_from_one_pass() {
+        let parsed = parse_release(
+            "[北宇治字幕组] 葬送的芙莉莲 / Sousou no Frieren [04v2][WebRip][1080p][HEVC_AAC][简日内嵌][MKV]",
+        );
+        assert_eq!(parsed.group.as_deref(), Some("北宇治字幕组"));
+        assert_eq!(parsed.titles, ["葬送的芙莉莲", "Sousou no Frieren"]);
+        assert_eq!((parsed.episode, parsed.version), (Some(4), Some(2)));
+        assert_eq!(parsed.source.as_deref(), Some("WebRip"));
+        assert_eq!(parsed.video_codec.as_deref(), Some("HEVC"));
+        assert_eq!(parsed.audio_codec.as_deref(), Some("AAC"));
+        assert_eq!(parsed.container.as_deref(), Some("MKV"));
+        assert!(!parsed.is_batch);
+    }
+
+    #[test]
+    fn special_markers_are_case_sensitive() {
+        let parsed = parse_release("[LoliHouse] Ed Edd n Eddy - 03 [1080p]");
+        assert_eq!(parsed.episode_kind, EpisodeKind::Main);
+        assert_eq!(parsed.episode, Some(3));
+        assert_eq!(parsed.titles, ["Ed Edd n Eddy"]);
+        assert_eq!(
+            parse_release("[VCB-Studio] Bocchi the Rock! [NCED02][Ma10p_1080p]").episode_kind,
+            EpisodeKind::Ed
+        );
+        assert_eq!(
+            parse_release("[Airota][Yuru Camp][ova][BDRip 1080p]").episode_kind,
+            EpisodeKind::Special
+        );
+    }
+
+    #[test]
+    fn episode_ranges_mark_batches() {
+        for (title, start, end) in [
+            ("[桜都字幕组] 孤独摇滚！ [01-12][BDRip][1080P]", 1, 12),
+            ("[DBD-Raws][葬送的芙莉莲][01-28TV全集][1080P][BDRip]", 1, 28),
+            ("[Moozzi2] Bocchi the Rock! [BDRip 1-12 1080p]", 1, 12),
+            ("[喵萌奶茶屋] 孤独摇滚！ 全12话 [BDRip][1080p]", 1, 12),
+            ("[织梦字幕组] 葬送的芙莉莲 第13-24话 [1080P]", 13, 24),
+        ] {
+            let parsed = parse_release(title);
+            assert_eq!(
+                (parsed.episode, parsed.episode_end),
+                (Some(start), Some(end)),
+                "{}",
+                title
+            );
+            assert!(parsed.is_batch, "{}", title);
+        }
+        let single = parse_release("[LoliHouse] 86 -Eighty Six- - 21 [WebRip 1080p]");
+        assert_eq!((single.episode, single.episode_end), (Some(21), None));
+        assert!(!single.is_batch);
+    }
+
+    #[test]
+    fn season_markers() {
+        for (title, season) in [
+            ("[LoliHouse] SPY×FAMILY S2 - 03 [WebRip 1080p]", 2),
+            ("[Sakurato] Spy x Family Season 2 - 04 [1080p]", 2),
+            ("[ANi] Oshi no Ko 2nd Season - 05 [1080P]", 2),
+            (
+                "[Up to 21°C] Shingeki no Kyojin Part 2 - 12 (CR 1920x1080)",
+                2,
+            ),
+            ("[ANi] 间谍过家家 第二季 - 06 [1080P]", 2),
+            ("【极影字幕社】【我心里危险的东西】【第2期】【14】", 2),
+            ("[Nekomoe kissaten] Kaguya-sama S03E07 [1080p]", 3),
+        ] {
+            assert_eq!(parse_release(title).season, Some(season), "{}", title);
+        }
+        let parsed = parse_release("[ANi] 间谍过家家 第二季 / SPY×FAMILY Season 2 - 06 [1080P]");
+        assert_eq!(parsed.titles, ["间谍过家家", "SPY×FAMILY"]);
+        assert_eq!(
+            parse_release("[LoliHouse] 86 -Eighty Six- - 21 [1080p]").season,
+            None
+        );
+    }
+
+    #[test]
+    fn special_and_fractional_episodes() {
+        for (title, kind, episode, sort) in [
+            (
+                "[LoliHouse] Sousou no Frieren - 13.5 [1080p]",
+                EpisodeKind::Main,
+                None,
+                13.5,
+            ),
+            (
+                "[LoliHouse] Sousou no Frieren - SP01 [1080p]",
+                EpisodeKind::Special,
+                None,
+                1.0,
+            ),
+            (
+                "[Airota][Yuru Camp][OVA 2][BDRip 1080p]",
+                EpisodeKind::Special,
+                None,
+                2.0,
+            ),
+            (
+                "[SweetSub] 孤独摇滚 总集篇 [WebRip][1080P]",
+                EpisodeKind::Special,
+                None,
+                1.0,
+            ),
+            (
+                "[VCB-Studio] Bocchi the Rock! [NCOP][Ma10p_1080p]",
+                EpisodeKind::Op,
+                None,
+                1.0,
+            ),
+            (
+                "[VCB-Studio] Bocchi the Rock! [NCED02][Ma10p_1080p]",
+                EpisodeKind::Ed,
+                None,
+                2.0,
+            ),
+            (
+                "[Airota][Yuru Camp Movie][BDRip 1080p]",
+                EpisodeKind::Movie,
+                Some(1),
+                1.0,
+            ),
+        ] {
+            let parsed = parse_release(title);
+            assert_eq!(parsed.episode_kind, kind, "{}", title);
+            assert_eq!(parsed.episode, episode, "{}", title);
+            assert_eq!(parsed.episode_sort, Some(sort), "{}", title);
+        }
+    }
+
+    #[test]
+    fn subtitle_languages_and_normalized_type() {
+        let parsed =
+            parse_release("[LoliHouse] Sousou no Frieren - 01 [WebRip 1080p][简繁日内封字幕]");
+        assert_eq!(parsed.subtitle_languages, ["chs", "cht", "jpn"]);
+        assert_eq!(parsed.subtitle_delivery, Some(SubtitleDelivery::Soft));
+        assert_eq!(parsed.subtitle_type.as_deref(), Some("简繁日内封"));
+
+        for (title, subtitle_type) in [
+            (
+                "[喵萌奶茶屋][Sousou no Frieren][01][1080p][简日双语]",
+                Some("中日双语"),
+            ),
+            (
+                "[桜都字幕组] 葬送的芙莉莲 [09][1080p][简繁内封]",
+                Some("简繁双语"),
+            ),
+            (
+                "[Nekomoe kissaten][Sousou no Frieren][03][1080p][CHS]",
+                Some("简体中文"),
+            ),
+            ("[c.c動漫][我推的孩子][07][BIG5][1080P]", Some("繁体中文")),
+            (
+                "[Raws] Sousou no Frieren - 18 (BS11 1920x1080) RAW",
+                Some("无字幕"),
+            ),
+            ("[SubsPlease] Sousou no Frieren - 07 (1080p)", None),
+        ] {
+            assert_eq!(
+                parse_release(title).subtitle_type.as_deref(),
+                subtitle_type,
+                "{}",
+                title
+            );
+        }
+    }
+
+    #[test]
+    fn technical_attributes() {
+        let parsed = parse_release(
+            "[XKsub] 葬送的芙莉莲 [19][HEVC-10bit 2160p HDR10 DV FLAC][国日双语][简繁日内封]",
+        );
+        assert_eq!(parsed.video_codec.as_deref(), Some("HEVC"));
+        assert_eq!(parsed.bit_depth, Some(10));
+        assert_eq!(parsed.hdr.as_deref(), Some("HDR10"));
+        assert_eq!(parsed.audio_codec.as_deref(), Some("FLAC"));
+        assert_eq!(parsed.resolution.as_deref(), Some("2160p"));
+
+        // 平台隐含 WEB-DL 片源，Ma10p 隐含 10bit HEVC
+        let parsed = parse_release("[SubsPlease] Frieren - 21 (1080p) [CR] MKV");
+        assert_eq!(parsed.platform.as_deref(), Some("CR"));
+        assert_eq!(parsed.source.as_deref(), Some("WEB-DL"));
+        assert_eq!(parsed.container.as_deref(), Some("MKV"));
+        let parsed = parse_release("[VCB-Studio] Sousou no Frieren [Ma10p_1080p]");
+        assert_eq!(
+            (parsed.video_codec.as_deref(), parsed.bit_depth),
+            (Some("HEVC"), Some(10))
+        );
+        let parsed = parse_release("[ReinForce] Bocchi the Rock! (BDRip 1920x1080 x264 FLAC)");
+        assert_eq!(parsed.video_codec.as_deref(), Some("AVC"));
+        assert_eq!(parsed.source.as_deref(), Some("BDRip"));
+    }
+
+    #[test]
+    fn apply_to_falls_back_to_feed_subtitle_type() {
+        let mut resource = Resource::default();
+        parse_release("[SubsPlease] Sousou no Frieren - 07 (1080p)")
+            .apply_to(&mut resource, Some("英语".to_string()));
+        assert_eq!(resource.subtitle_type.as_deref(), Some("英语"));
+        assert_eq!(resource.episode_number, Some(7));
+        assert_eq!(resource.parser_version, Some(PARSER_VERSION));
+
+        parse_release("[SubsPlease] Sousou no Frieren - 07 (1080p)").apply_to(&mut resource, None);
+        assert_eq!(resource.subtitle_type.as_deref(), Some("其他"));
+        // 标题中识别出的字幕类型优先于订阅源
+        parse_release("[桜都字幕组] 葬送的芙莉莲 [09][1080p][简繁内封]")
+            .apply_to(&mut resource, Some("英语".to_string()));
+        assert_eq!(resource.subtitle_type.as_deref(), Some("简繁双语"));
+    }
+
+    #[test]
+    fn datetime_uses_source_timezone() {
+        let shanghai = parse_datetime_to_timestamp("2024/07/05 23:30", chrono_tz::Asia::Shanghai);
+        let utc = parse_datetime_to_timestamp("2024/07/05 23:30", Tz::UTC);
+        assert_eq!(utc.unwrap() - shanghai.unwrap(), 8 * 3600 * 1000);
+        // RFC 2822 自带时区，与来源时区无关
+        let rfc = "Fri, 05 Jul 2024 23:30:00 +0800";
+        assert_eq!(parse_datetime_to_timestamp(rfc, Tz::UTC), shanghai);
+        assert_eq!(
+            parse_datetime_to_timestamp(rfc, chrono_tz::Asia::Shanghai),
+            shanghai
+        );
+    }
+
+    #[test]
+    fn file_size_round_trip() {
+        assert_eq!(parse_file_size_bytes("1.5GB"), Some(1_610_612_736));
+        assert_eq!(parse_file_size_bytes("350MB"), Some(367_001_600));
+        assert_eq!(parse_file_size_bytes("1.4 GiB"), Some(1_503_238_553));
+        assert_eq!(parse_file_size_bytes("123456"), Some(123_456));
+        assert_eq!(parse_file_size_bytes("未知"), None);
+        assert_eq!(format_file_size(367_001_600), "350.0MB");
+        assert_eq!(format_file_size(1_610_612_736), "1.5GB");
+    }
+}
@@ -0,0 +1,170 @@
+use crate::models::Anime;
+
+// =============================================================================
+// 按标题将非 Mikan 来源的资源关联到已入库番剧
+// =============================================================================
+
+/// 纯ASCII标题过短时容易误匹配（如 "K"、"ONE"），要求的最小长度
+const MIN_ASCII_TITLE_LEN: usize = 4;
+/// 含中日文字符的标题最小长度
+const MIN_CJK_TITLE_LEN: usize = 2;
+
+/// 标题归一化：全角转半角、转小写，只保留字母数字与中日文字符
+pub fn normalize_title(title: &str) -> String {
+    title
+        .chars()
+        .map(|c| match c {
+            '\u{FF01}'..='\u{FF5E}' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+            _ => c,
+        })
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// 标题相似度（0~1）：归一化后相同为1，否则取字符二元组的 Dice 系数
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = normalize_title(a).chars().collect();
+    let b: Vec<char> = normalize_title(b).chars().collect();
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    if a.len() < 2 || b.len() < 2 {
+        return 0.0;
+    }
+    let mut bigrams: Vec<(char, char)> = a.windows(2).map(|w| (w[0], w[1])).collect();
+    let total = bigrams.len() + b.len() - 1;
+    let mut common = 0;
+    for w in b.windows(2) {
+        if let Some(pos) = bigrams.iter().position(|g| *g == (w[0], w[1])) {
+            bigrams.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / total as f64
+}
+
+/// 去除标点空格后，两侧同为ASCII字母或同为数字的字符属于同一个词
+fn same_word(a: char, b: char) -> bool {
+    (a.is_ascii_alphabetic() && b.is_ascii_alphabetic())
+        || (a.is_ascii_digit() && b.is_ascii_digit())
+}
+
+struct TitleEntry {
+    normalized: Vec<char>,
+    mikan_id: i64,
+}
+
+/// 番剧标题索引，按"资源标题包含番剧标题"匹配，标题两端须落在词边界上，
+/// 使 "mono" 不会命中 "Monogatari"；多个命中时取最长标题，使 "xxx 第二季" 优先于 "xxx"
+pub struct TitleMatcher {
+    entries: Vec<TitleEntry>,
+}
+
+impl TitleMatcher {
+    pub fn new(animes: &[Anime]) -> Self {
+        let mut matcher = Self {
+            entries: Vec::new(),
+        };
+        for anime in animes {
+            matcher.add_title(&anime.title, anime.mikan_id);
+            if let Some(original_title) = &anime.original_title {
+                matcher.add_title(original_title, anime.mikan_id);
+            }
+        }
+        matcher
+    }
+
+    /// 为番剧追加一个可匹配的标题
+    pub fn add_title(&mut self, title: &str, mikan_id: i64) {
+        let normalized = normalize_title(title);
+        let len = normalized.chars().count();
+        let min_len = if normalized.is_ascii() {
+            MIN_ASCII_TITLE_LEN
+        } else {
+            MIN_CJK_TITLE_LEN
+        };
+        if len >= min_len {
+            self.entries.push(TitleEntry {
+                normalized: normalized.chars().collect(),
+                mikan_id,
+            });
+        }
+    }
+
+    /// 返回与资源标题匹配的番剧mikan_id
+    pub fn match_title(&self, release_title: &str) -> Option<i64> {
+        // 逐词归一化，记录每个位置前是否有被去掉的分隔符
+        let mut chars = Vec::new();
+        let mut separated = Vec::new();
+        for word in release_title.split(|c: char| !c.is_alphanumeric()) {
+            let word: Vec<char> = normalize_title(word).chars().collect();
+            for (i, c) in word.into_iter().enumerate() {
+                separated.push(i == 0);
+                chars.push(c);
+            }
+        }
+        separated.push(true);
+
+        self.entries
+            .iter()
+            .filter(|entry| {
+                let len = entry.normalized.len();
+                (0..(chars.len() + 1).saturating_sub(len)).any(|start| {
+                    let end = start + len;
+                    chars[start..end] == entry.normalized[..]
+                        && (separated[start] || !same_word(chars[start - 1], entry.normalized[0]))
+                        && (separated[end] || !same_word(chars[end], entry.normalized[len - 1]))
+                })
+            })
+            .max_by_key(|entry| entry.normalized.len())
+            .map(|entry| entry.mikan_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher_for(titles: &[(&str, i64)]) -> TitleMatcher {
+        let mut matcher = TitleMatcher::new(&[]);
+        for (title, mikan_id) in titles {
+            matcher.add_title(title, *mikan_id);
+        }
+        matcher
+    }
+
+    #[test]
+    fn titles_match_on_word_boundaries() {
+        let matcher = matcher_for(&[("Mono", 1), ("Bakemonogatari", 2)]);
+        assert_eq!(
+            matcher.match_title("[SubsPlease] Mono - 03 (1080p)"),
+            Some(1)
+        );
+        assert_eq!(matcher.match_title("[Sub] Monogatari Series - 01"), None);
+        assert_eq!(matcher.match_title("[Sub] Bakemonogatari - 01"), Some(2));
+        // 中日文字符不区分词边界，标点与下划线视为分隔符
+        let matcher = matcher_for(&[("葬送的芙莉莲", 3), ("Sousou no Frieren", 3)]);
+        assert_eq!(
+            matcher.match_title("【喵萌奶茶屋】葬送的芙莉莲第02集"),
+            Some(3)
+        );
+        assert_eq!(matcher.match_title("[Sub] Sousou_no_Frieren_-_02"), Some(3));
+        assert_eq!(matcher.match_title("[Sub] Sousou no Frierens 02"), None);
+    }
+
+    #[test]
+    fn longest_title_wins() {
+        let matcher = matcher_for(&[("Oshi no Ko", 1), ("Oshi no Ko 2nd Season", 2)]);
+        assert_eq!(matcher.match_title("[Sub] Oshi no Ko - 11"), Some(1));
+        assert_eq!(
+            matcher.match_title("[Sub] Oshi no Ko 2nd Season - 01"),
+            Some(2)
+        );
+        let matcher = matcher_for(&[("间谍过家家 第二季", 4), ("间谍过家家", 3)]);
+        assert_eq!(matcher.match_title("[组] 间谍过家家 第二季 - 01"), Some(4));
+    }
+}
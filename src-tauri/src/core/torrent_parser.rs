@@ -0,0 +1,57 @@
+use crate::error::{AppError, DomainError, Result};
+use librqbit::{torrent_from_bytes, ByteBuf};
+
+// =============================================================================
+// 解析 .torrent 文件，提取 info hash、精确大小与文件列表
+// =============================================================================
+
+#[derive(Debug, Clone)]
+pub struct TorrentInfo {
+    pub info_hash: String,
+    pub name: Option<String>,
+    pub total_size: i64,
+    pub piece_length: i64,
+    pub files: Vec<TorrentFileEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TorrentFileEntry {
+    // 文件在种子中的序号，下载时用于选择文件，包含被跳过的填充文件
+    pub file_index: i64,
+    // 种子内的相对路径，以 "/" 分隔
+    pub path: String,
+    pub size: i64,
+}
+
+pub fn parse_torrent(bytes: &[u8]) -> Result<TorrentInfo> {
+    let invalid = |e: anyhow::Error| {
+        AppError::Domain(DomainError::Serialization(format!("解析种子失败: {}", e)))
+    };
+    let meta = torrent_from_bytes::<ByteBuf>(bytes).map_err(invalid)?;
+    let info = &meta.info;
+    let mut files = Vec::new();
+    let mut total_size = 0i64;
+    for (index, file) in info.iter_file_details().map_err(invalid)?.enumerate() {
+        // BEP-47 填充文件只用于对齐分块，不展示
+        if file.attrs().padding {
+            continue;
+        }
+        let path = file.filename.to_vec().map_err(invalid)?.join("/");
+        total_size += file.len as i64;
+        files.push(TorrentFileEntry {
+            file_index: index as i64,
+            path,
+            size: file.len as i64,
+        });
+    }
+    Ok(TorrentInfo {
+        info_hash: meta.info_hash.as_string(),
+        name: info
+            .name
+            .as_ref()
+            .map(|name| String::from_utf8_lossy(name.as_ref()).into_owned()),
+        total_size,
+        piece_length: info.piece_length as i64,
+        files,
+    })
+}
@@ -0,0 +1,187 @@
+use crate::config::TorznabEndpoint;
+use crate::core::anime_parser::{AnimeParser, FeedMapping};
+use crate::core::rss::parse_rss;
+use crate::core::text_parser;
+use crate::core::FeedItem;
+use crate::error::Result;
+use crate::types::crawler::CrawlerTaskCreate;
+
+// Torznab "TV/Anime" 分类
+const DEFAULT_CATEGORY: i64 = 5070;
+
+/// Torznab 索引器（Jackett/Prowlarr 等）：按关键词搜索，结果按标题匹配到已入库番剧
+pub struct TorznabParser {
+    pub name: String,
+    pub url: String,
+    pub api_key: Option<String>,
+    pub categories: Vec<i64>,
+}
+
+impl TorznabParser {
+    pub fn new(endpoint: &TorznabEndpoint) -> Self {
+        Self {
+            name: endpoint.name.clone(),
+            url: endpoint.url.clone(),
+            api_key: endpoint.api_key.clone(),
+            categories: endpoint
+                .categories
+                .clone()
+                .filter(|c| !c.is_empty())
+                .unwrap_or_else(|| vec![DEFAULT_CATEGORY]),
+        }
+    }
+}
+
+impl AnimeParser for TorznabParser {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn base_url(&self) -> &str {
+        &self.url
+    }
+
+    fn feed_url(&self, params: &CrawlerTaskCreate) -> Option<String> {
+        let categories = self
+            .categories
+            .iter()
+            .map(|c| c.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut query = vec![("t", "search"), ("cat", categories.as_str())];
+        if let Some(api_key) = self.api_key.as_deref() {
+            query.push(("apikey", api_key));
+        }
+        if let Some(keyword) = params.keyword.as_deref().filter(|k| !k.trim().is_empty()) {
+            query.push(("q", keyword.trim()));
+        }
+        reqwest::Url::parse_with_params(&self.url, &query)
+            .ok()
+            .map(|url| url.to_string())
+    }
+
+    fn feed_mapping(&self) -> FeedMapping {
+        FeedMapping::TitleMatch
+    }
+
+    // 索引器多为英文站点，有别名时优先按别名搜索
+    fn prefers_alias_keyword(&self) -> bool {
+        true
+    }
+
+    fn parse_feed(&self, xml: &str) -> Result<Vec<FeedItem>> {
+        let doc = parse_rss(xml)?;
+        Ok(doc
+            .channel
+            .items
+            .into_iter()
+            .filter_map(|item| {
+                let download_url = item
+                    .enclosure
+                    .as_ref()
+                    .map(|e| e.url.clone())
+                    .or_else(|| item.link.clone());
+                let magnet_url = item
+                    .attr("magneturl")
+                    .map(str::to_string)
+                    .or_else(|| download_url.clone().filter(|u| u.starts_with("magnet:")));
+                let magnet_hash = item
+                    .attr("infohash")
+                    .map(|hash| hash.trim().to_lowercase())
+                    .filter(|hash| hash.len() == 40)
+                    .or_else(|| {
+                        magnet_url
+                            .as_deref()
+                            .and_then(text_parser::parse_magnet_hash)
+                    })?;
+                let size_bytes = item
+                    .size
+                    .as_deref()
+                    .or(item.attr("size"))
+                    .and_then(text_parser::parse_file_size_bytes)
+                    .or_else(|| item.enclosure.as_ref().and_then(|e| e.length))
+                    .filter(|size| *size > 0);
+                let seeders = item.attr("seeders").and_then(|v| v.parse::<i64>().ok());
+                // Torznab 的 peers 包含做种者
+                let leechers = item
+                    .attr("leechers")
+                    .and_then(|v| v.parse::<i64>().ok())
+                    .or_else(|| {
+                        let peers = item.attr("peers")?.parse::<i64>().ok()?;
+                        Some((peers - seeders.unwrap_or(0)).max(0))
+                    });
+                Some(FeedItem {
+                    page_url: item.guid.clone(),
+                    magnet_url: Some(
+                        magnet_url
+                            .unwrap_or_else(|| format!("magnet:?xt=urn:btih:{}", magnet_hash)),
+                    ),
+                    magnet_hash: Some(magnet_hash),
+                    torrent_url: download_url.filter(|u| !u.starts_with("magnet:")),
+                    file_size: size_bytes.map(text_parser::format_file_size),
+                    size_bytes,
+                    release_date: item.pub_date.as_deref().and_then(|date| {
+                        text_parser::parse_datetime_to_timestamp(date, self.timezone())
+                    }),
+                    subtitle_type: None,
+                    seeders,
+                    leechers,
+                    group_name: text_parser::parse_release_group(&item.title),
+                    title: item.title.trim().to_string(),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_feed_reads_torznab_attrs() {
+        let parser = TorznabParser::new(&TorznabEndpoint {
+            name: "jackett".to_string(),
+            url: "http://127.0.0.1:9117/api/v2.0/indexers/all/results/torznab/api".to_string(),
+            api_key: Some("secret".to_string()),
+            categories: None,
+            enabled: None,
+        });
+        let items = parser
+            .parse_feed(include_str!("testdata/torznab_feed.xml"))
+            .unwrap();
+        // 既无 infohash 也无磁力链接的条目被跳过
+        assert_eq!(items.len(), 2);
+
+        let item = &items[0];
+        assert_eq!(
+            item.magnet_hash.as_deref(),
+            Some("abcdef0123456789abcdef0123456789abcdef01")
+        );
+        assert!(item
+            .magnet_url
+            .as_deref()
+            .unwrap()
+            .starts_with("magnet:?xt=urn:btih:ABCDEF"));
+        assert!(item
+            .torrent_url
+            .as_deref()
+            .unwrap()
+            .starts_with("http://127.0.0.1:9117/dl/nyaasi/"));
+        assert_eq!(item.size_bytes, Some(1_503_238_554));
+        assert_eq!(item.seeders, Some(1523));
+        // peers 1565 减去做种 1523
+        assert_eq!(item.leechers, Some(42));
+
+        // 磁力链接作为 link、大小与下载人数来自 torznab:attr
+        let item = &items[1];
+        assert_eq!(
+            item.magnet_hash.as_deref(),
+            Some("2222222222222222222222222222222222222222")
+        );
+        assert_eq!(item.torrent_url, None);
+        assert_eq!(item.size_bytes, Some(734_003_200));
+        assert_eq!(item.seeders, Some(12));
+        assert_eq!(item.leechers, Some(4));
+    }
+}
@@ -240,18 +240,30 @@ pub fn run() -> crate::error::Result<()> {
             let pool_arc = init_db(&db_path);
 
             // 4. 配置加载
-            let config = load_config();
+            let mut config = load_config();
+            {
+                // HTTP校验值存储默认与数据库放在同一目录
+                let http = config.http.get_or_insert_with(Default::default);
+                if http.validator_store.is_none() {
+                    let store_path = db_path.with_file_name("http_validators.json");
+                    http.validator_store = Some(store_path.to_string_lossy().to_string());
+                }
+                if http.fixture_dir.is_none() {
+                    let fixture_dir = db_path.with_file_name("http_fixtures");
+                    http.fixture_dir = Some(fixture_dir.to_string_lossy().to_string());
+                }
+                if let Some(mode) = &http.mode {
+                    tracing::info!("HTTP模式: {:?}", mode);
+                }
+            }
 
-            // 5. Worker 启动前，批量将所有Running状态的任务标记为Failed
+            // 5. Worker 启动前，将上次中断的Running任务重新放回队列，从断点继续
             {
                 use crate::repositories::crawler_task::CrawlerTaskRepository;
                 let repo = CrawlerTaskRepository::new(&pool_arc);
-                let msg = "应用重启，任务中断";
-                match tauri::async_runtime::block_on(async {
-                    repo.mark_all_running_as_failed(msg).await
-                }) {
-                    Ok(n) => tracing::info!("已将{n}个Running任务标记为Failed"),
-                    Err(e) => tracing::error!("批量标记Running任务为Failed失败: {e}"),
+                match tauri::async_runtime::block_on(async { repo.requeue_running_tasks().await }) {
+                    Ok(n) => tracing::info!("已将{n}个中断的Running任务重新放回队列"),
+                    Err(e) => tracing::error!("恢复中断的Running任务失败: {e}"),
                 }
             }
 
@@ -343,6 +355,17 @@ pub fn run() -> crate::error::Result<()> {
             get_episode_resources,
             search_library,
             get_anime_resources,
+            list_anime_aliases,
+            add_anime_alias,
+            remove_anime_alias,
+            get_torrent_manifest,
+            list_anime_mappings,
+            add_anime_mapping,
+            remove_anime_mapping,
+            reconcile_bangumi_ids,
+            list_bangumi_match_candidates,
+            confirm_bangumi_match,
+            reject_bangumi_match,
             // Crawler commands
             create_crawler_task,
             get_crawler_task_status,
@@ -350,6 +373,12 @@ pub fn run() -> crate::error::Result<()> {
             get_crawler_task,
             cancel_crawler_task,
             delete_crawler_task,
+            resume_crawler_task,
+            list_quarantined_pages,
+            reparse_quarantined_page,
+            discard_quarantined_page,
+            list_resource_sources,
+            evaluate_text_parser,
             // Subscription commands
             subscribe,
             unsubscribe,
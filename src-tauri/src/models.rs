@@ -106,16 +106,86 @@ pub struct UserSubscription {
     pub images: Option<String>, // 存储 BangumiImages 的 JSON 字符串
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+// 剧集类型，对应 Bangumi 章节类型：0 本篇、1 特别篇、2 OP、3 ED；剧场版按本篇处理
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeKind {
+    #[default]
+    Main,
+    Special,
+    Op,
+    Ed,
+    Movie,
+}
+
+impl EpisodeKind {
+    pub fn from_bangumi_type(episode_type: i64) -> Option<Self> {
+        match episode_type {
+            0 => Some(Self::Main),
+            1 => Some(Self::Special),
+            2 => Some(Self::Op),
+            3 => Some(Self::Ed),
+            _ => None,
+        }
+    }
+
+    /// 正片与剧场版使用 episode_number 编号
+    pub fn is_main(self) -> bool {
+        matches!(self, Self::Main | Self::Movie)
+    }
+}
+
+// 字幕交付方式：内封（软字幕，封装于容器）、内嵌（硬字幕）、外挂（独立字幕文件）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleDelivery {
+    Soft,
+    Hard,
+    External,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct Resource {
     pub id: Option<i64>,
     pub mikan_id: i64,
     pub subtitle_group_id: i64,
     pub episode_number: Option<i32>,
+    // 合集的结束集数，episode_number 为起始集数
+    pub episode_end: Option<i32>,
+    pub season: Option<i32>,
+    pub episode_kind: EpisodeKind,
+    // 在剧集类型内的精确编号，可为小数，如13.5、SP2
+    pub episode_sort: Option<f64>,
+    // 发布版本，未标注时为空（视为 v1）
+    pub version: Option<i32>,
+    // 被同组同集的更高版本取代时指向该资源
+    pub superseded_by: Option<i64>,
     pub title: String,
     pub file_size: Option<String>,
     pub resolution: Option<String>,
     pub subtitle_type: Option<String>,
+    // 字幕语言代码，逗号分隔，如 "chs,cht,jpn"
+    pub subtitle_languages: Option<String>,
+    pub subtitle_delivery: Option<SubtitleDelivery>,
+    // 音轨语言代码，未标注时为空
+    pub audio_language: Option<String>,
+    // 视频编码：HEVC / AVC / AV1 / VP9
+    pub video_codec: Option<String>,
+    pub bit_depth: Option<i32>,
+    // HDR 格式：HDR10 / HDR10+ / HLG / DV
+    pub hdr: Option<String>,
+    // 音频编码：AAC / FLAC / Opus 等
+    pub audio_codec: Option<String>,
+    // 片源：BDRip / WebRip / WEB-DL 等，与资源来源站点 source 区分
+    pub release_source: Option<String>,
+    // 流媒体平台：Baha / CR / B-Global 等
+    pub platform: Option<String>,
+    // 封装格式：MKV / MP4
+    pub container: Option<String>,
+    // 解析标题所用的解析器版本，早于此字段入库的资源为空
+    pub parser_version: Option<i32>,
     pub magnet_url: Option<String>,
     pub torrent_url: Option<String>,
     pub play_url: Option<String>,
@@ -123,11 +193,126 @@ pub struct Resource {
     pub release_date: Option<i64>,
     pub created_at: Option<i64>,
     pub updated_at: Option<i64>,
+    // 资源来源名称，对应 SourceRegistry 中注册的来源
+    pub source: String,
+    // 资源健康度，来源未提供时为空
+    pub seeders: Option<i64>,
+    pub leechers: Option<i64>,
+    pub size_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AnimeAlias {
+    pub id: Option<i64>,
+    pub mikan_id: i64,
+    pub alias: String,
+    pub created_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum TorrentMetadataStatus {
+    Fetched,
+    Failed,
+}
+
+// 资源种子的元数据，失败时记录原因与尝试次数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct TorrentMetadata {
+    pub resource_id: i64,
+    pub status: TorrentMetadataStatus,
+    pub info_hash: Option<String>,
+    pub name: Option<String>,
+    // 精确字节数，不含填充文件
+    pub total_size: Option<i64>,
+    pub piece_length: Option<i64>,
+    pub file_count: Option<i64>,
+    pub error_message: Option<String>,
+    pub attempts: i64,
+    pub fetched_at: i64,
+}
+
+// 种子内的单个文件，file_index 对应下载时选择文件的序号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct TorrentFile {
+    pub id: Option<i64>,
+    pub resource_id: i64,
+    pub file_index: i64,
+    pub path: String,
+    pub size: i64,
+}
+
+// 用户维护的 Mikan 番剧与 Bangumi 条目对应关系
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct AnimeBangumiMapping {
+    pub id: Option<i64>,
+    pub mikan_id: i64,
+    pub bangumi_id: i64,
+    pub created_at: Option<i64>,
+    // 只匹配该季度的资源，为空时不限
+    pub season: Option<i32>,
+    // Bangumi 集数 = 资源集数 - episode_offset
+    pub episode_offset: i32,
+}
+
+// 某个 Bangumi 条目下一部 Mikan 番剧的资源如何换算集数，未配置映射的番剧偏移为0
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct EpisodeMapping {
+    pub mikan_id: i64,
+    pub season: Option<i32>,
+    pub episode_offset: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum MatchCandidateStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+// 缺少 bangumi_id 的番剧在 Bangumi 上的候选条目
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct BangumiMatchCandidate {
+    pub id: Option<i64>,
+    pub mikan_id: i64,
+    pub bangumi_id: i64,
+    pub name: String,
+    pub name_cn: Option<String>,
+    pub air_date: Option<String>,
+    // 0~1，由标题相似度与放送日期接近程度计算
+    pub confidence: f64,
+    pub status: MatchCandidateStatus,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+#[sqlx(type_name = "TEXT")]
+#[serde(rename_all = "lowercase")]
+pub enum QuarantinePageKind {
+    List,
+    Detail,
+}
+
+// 解析结果可疑而被隔离的页面
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ParseQuarantine {
+    pub id: Option<i64>,
+    pub task_id: Option<i64>,
+    pub source: String,
+    pub page_kind: QuarantinePageKind,
+    pub url: String,
+    pub mikan_id: Option<i64>,
+    pub reason: String,
+    pub raw_html: String,
+    pub created_at: i64,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
 pub struct EpisodeResourceCount {
-    pub episode_number: i32,
+    pub episode_sort: f64,
     pub resource_count: i64,
 }
 
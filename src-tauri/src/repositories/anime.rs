@@ -1,8 +1,9 @@
 use crate::error::Result;
-use crate::models::Anime;
+use crate::models::{Anime, EpisodeMapping};
 use crate::repositories::base::Repository;
 use async_trait::async_trait;
-use sqlx::{SqlitePool, Transaction};
+use sqlx::{QueryBuilder, SqlitePool, Transaction};
+use std::collections::HashSet;
 
 pub struct AnimeRepository<'a> {
     pool: &'a SqlitePool,
@@ -23,6 +24,96 @@ impl<'a> AnimeRepository<'a> {
         )
     }
 
+    /// 返回给定mikan_id中已入库的部分
+    pub async fn existing_ids(&self, mikan_ids: &[i64]) -> Result<HashSet<i64>> {
+        if mikan_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let mut builder = QueryBuilder::new("SELECT mikan_id FROM anime WHERE mikan_id IN (");
+        let mut separated = builder.separated(", ");
+        for id in mikan_ids {
+            separated.push_bind(id);
+        }
+        separated.push_unseparated(")");
+        let rows: Vec<i64> = builder.build_query_scalar().fetch_all(self.pool).await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Bangumi 条目对应的番剧：优先使用 anime_bangumi_mapping 中的映射，
+    /// 有映射的番剧不再按 anime.bangumi_id 匹配
+    pub async fn mikan_ids_by_bangumi_id(&self, bangumi_id: i64) -> Result<Vec<i64>> {
+        Ok(sqlx::query_scalar(
+            "SELECT mikan_id FROM anime_bangumi_mapping WHERE bangumi_id = ? \
+             UNION \
+             SELECT mikan_id FROM anime WHERE bangumi_id = ? \
+             AND mikan_id NOT IN (SELECT mikan_id FROM anime_bangumi_mapping) \
+             ORDER BY mikan_id",
+        )
+        .bind(bangumi_id)
+        .bind(bangumi_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 与 mikan_ids_by_bangumi_id 相同的番剧，附带各自的季度与集数偏移
+    pub async fn episode_mappings_by_bangumi_id(
+        &self,
+        bangumi_id: i64,
+    ) -> Result<Vec<EpisodeMapping>> {
+        Ok(sqlx::query_as::<_, EpisodeMapping>(
+            "SELECT mikan_id, season, episode_offset FROM anime_bangumi_mapping WHERE bangumi_id = ? \
+             UNION \
+             SELECT mikan_id, NULL AS season, 0 AS episode_offset FROM anime WHERE bangumi_id = ? \
+             AND mikan_id NOT IN (SELECT mikan_id FROM anime_bangumi_mapping) \
+             ORDER BY mikan_id",
+        )
+        .bind(bangumi_id)
+        .bind(bangumi_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 缺少 bangumi_id 且没有待确认候选的番剧，新番优先
+    pub async fn list_missing_bangumi_id(&self, limit: i64) -> Result<Vec<Anime>> {
+        Ok(sqlx::query_as::<_, Anime>(
+            "SELECT * FROM anime WHERE bangumi_id = 0 AND mikan_id NOT IN \
+             (SELECT mikan_id FROM bangumi_match_candidate WHERE status = ?) \
+             ORDER BY mikan_id DESC LIMIT ?",
+        )
+        .bind(crate::models::MatchCandidateStatus::Pending)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 关联 Bangumi 条目
+    pub async fn set_bangumi_id(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Sqlite>,
+        mikan_id: i64,
+        bangumi_id: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE anime SET bangumi_id = ?, bangumi_url = ?, updated_at = ? WHERE mikan_id = ?",
+        )
+        .bind(bangumi_id)
+        .bind(format!("https://bgm.tv/subject/{}", bangumi_id))
+        .bind(chrono::Utc::now().timestamp_millis())
+        .bind(mikan_id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// 已被用户订阅的番剧
+    pub async fn list_subscribed(&self) -> Result<Vec<Anime>> {
+        Ok(sqlx::query_as::<_, Anime>(
+            "SELECT * FROM anime WHERE bangumi_id > 0 AND bangumi_id IN (SELECT DISTINCT bangumi_id FROM user_subscriptions)",
+        )
+        .fetch_all(self.pool)
+        .await?)
+    }
+
     pub async fn search_by_title(
         &self,
         title: &str,
@@ -56,7 +147,6 @@ impl<'a> AnimeRepository<'a> {
         tx: &mut Transaction<'_, sqlx::Sqlite>,
         animes: &[Anime],
     ) -> Result<()> {
-        use sqlx::QueryBuilder;
         if animes.is_empty() {
             return Ok(());
         }
@@ -97,13 +187,13 @@ impl<'a> AnimeRepository<'a> {
         }
         builder.push(
             " ON CONFLICT(mikan_id) DO UPDATE SET \
-                bangumi_id = excluded.bangumi_id,\
+                bangumi_id = CASE WHEN excluded.bangumi_id > 0 THEN excluded.bangumi_id ELSE anime.bangumi_id END,\
                 title = excluded.title,\
                 original_title = excluded.original_title,\
                 broadcast_day = excluded.broadcast_day,\
                 broadcast_start = excluded.broadcast_start,\
                 official_website = excluded.official_website,\
-                bangumi_url = excluded.bangumi_url,\
+                bangumi_url = COALESCE(excluded.bangumi_url, anime.bangumi_url),\
                 description = excluded.description,\
                 status = excluded.status,\
                 updated_at = excluded.updated_at",
@@ -0,0 +1,91 @@
+use crate::error::Result;
+use crate::models::AnimeAlias;
+use crate::repositories::base::Repository;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+pub struct AnimeAliasRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> AnimeAliasRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_by_mikan_id(&self, mikan_id: i64) -> Result<Vec<AnimeAlias>> {
+        Ok(sqlx::query_as::<_, AnimeAlias>(
+            "SELECT * FROM anime_alias WHERE mikan_id = ? ORDER BY id",
+        )
+        .bind(mikan_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 按 (mikan_id, alias) 查找，用于返回新建后的别名
+    pub async fn get_by_alias(&self, mikan_id: i64, alias: &str) -> Result<Option<AnimeAlias>> {
+        Ok(sqlx::query_as::<_, AnimeAlias>(
+            "SELECT * FROM anime_alias WHERE mikan_id = ? AND alias = ?",
+        )
+        .bind(mikan_id)
+        .bind(alias)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+}
+
+#[async_trait]
+impl<'a> Repository<AnimeAlias, i64> for AnimeAliasRepository<'a> {
+    async fn create(&self, alias: &AnimeAlias) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO anime_alias (mikan_id, alias, created_at) VALUES (?, ?, ?)
+             ON CONFLICT(mikan_id, alias) DO NOTHING",
+        )
+        .bind(alias.mikan_id)
+        .bind(&alias.alias)
+        .bind(alias.created_at)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<AnimeAlias>> {
+        Ok(
+            sqlx::query_as::<_, AnimeAlias>("SELECT * FROM anime_alias WHERE id = ?")
+                .bind(id)
+                .fetch_optional(self.pool)
+                .await?,
+        )
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<AnimeAlias>> {
+        let query = if limit > 0 {
+            "SELECT * FROM anime_alias ORDER BY id LIMIT ? OFFSET ?"
+        } else {
+            "SELECT * FROM anime_alias ORDER BY id LIMIT -1 OFFSET 0"
+        };
+        Ok(sqlx::query_as::<_, AnimeAlias>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    async fn update(&self, alias: &AnimeAlias) -> Result<()> {
+        sqlx::query("UPDATE anime_alias SET mikan_id = ?, alias = ? WHERE id = ?")
+            .bind(alias.mikan_id)
+            .bind(&alias.alias)
+            .bind(alias.id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM anime_alias WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
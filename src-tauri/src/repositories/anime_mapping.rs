@@ -0,0 +1,110 @@
+use crate::error::Result;
+use crate::models::AnimeBangumiMapping;
+use crate::repositories::base::Repository;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+pub struct AnimeMappingRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> AnimeMappingRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn list_by_mikan_id(&self, mikan_id: i64) -> Result<Vec<AnimeBangumiMapping>> {
+        Ok(sqlx::query_as::<_, AnimeBangumiMapping>(
+            "SELECT * FROM anime_bangumi_mapping WHERE mikan_id = ? ORDER BY bangumi_id",
+        )
+        .bind(mikan_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    pub async fn list_by_bangumi_id(&self, bangumi_id: i64) -> Result<Vec<AnimeBangumiMapping>> {
+        Ok(sqlx::query_as::<_, AnimeBangumiMapping>(
+            "SELECT * FROM anime_bangumi_mapping WHERE bangumi_id = ? ORDER BY mikan_id",
+        )
+        .bind(bangumi_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 按 (mikan_id, bangumi_id) 查找，用于返回新建后的映射
+    pub async fn get_by_pair(
+        &self,
+        mikan_id: i64,
+        bangumi_id: i64,
+    ) -> Result<Option<AnimeBangumiMapping>> {
+        Ok(sqlx::query_as::<_, AnimeBangumiMapping>(
+            "SELECT * FROM anime_bangumi_mapping WHERE mikan_id = ? AND bangumi_id = ?",
+        )
+        .bind(mikan_id)
+        .bind(bangumi_id)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+}
+
+#[async_trait]
+impl<'a> Repository<AnimeBangumiMapping, i64> for AnimeMappingRepository<'a> {
+    async fn create(&self, mapping: &AnimeBangumiMapping) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO anime_bangumi_mapping (mikan_id, bangumi_id, created_at, season, episode_offset) VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(mikan_id, bangumi_id) DO UPDATE SET season = excluded.season, episode_offset = excluded.episode_offset",
+        )
+        .bind(mapping.mikan_id)
+        .bind(mapping.bangumi_id)
+        .bind(mapping.created_at)
+        .bind(mapping.season)
+        .bind(mapping.episode_offset)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<AnimeBangumiMapping>> {
+        Ok(sqlx::query_as::<_, AnimeBangumiMapping>(
+            "SELECT * FROM anime_bangumi_mapping WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<AnimeBangumiMapping>> {
+        let query = if limit > 0 {
+            "SELECT * FROM anime_bangumi_mapping ORDER BY id LIMIT ? OFFSET ?"
+        } else {
+            "SELECT * FROM anime_bangumi_mapping ORDER BY id LIMIT -1 OFFSET 0"
+        };
+        Ok(sqlx::query_as::<_, AnimeBangumiMapping>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    async fn update(&self, mapping: &AnimeBangumiMapping) -> Result<()> {
+        sqlx::query(
+            "UPDATE anime_bangumi_mapping SET mikan_id = ?, bangumi_id = ?, season = ?, episode_offset = ? WHERE id = ?",
+        )
+        .bind(mapping.mikan_id)
+        .bind(mapping.bangumi_id)
+        .bind(mapping.season)
+        .bind(mapping.episode_offset)
+        .bind(mapping.id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM anime_bangumi_mapping WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
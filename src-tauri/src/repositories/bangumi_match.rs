@@ -0,0 +1,143 @@
+use crate::error::Result;
+use crate::models::{BangumiMatchCandidate, MatchCandidateStatus};
+use crate::repositories::base::Repository;
+use async_trait::async_trait;
+use sqlx::{SqlitePool, Transaction};
+
+pub struct BangumiMatchRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> BangumiMatchRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 待确认的候选，按番剧分组、置信度降序
+    pub async fn list_pending(&self, mikan_id: Option<i64>) -> Result<Vec<BangumiMatchCandidate>> {
+        Ok(sqlx::query_as::<_, BangumiMatchCandidate>(
+            "SELECT * FROM bangumi_match_candidate
+             WHERE status = ? AND (? IS NULL OR mikan_id = ?)
+             ORDER BY mikan_id, confidence DESC",
+        )
+        .bind(MatchCandidateStatus::Pending)
+        .bind(mikan_id)
+        .bind(mikan_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    pub async fn set_status(&self, id: i64, status: MatchCandidateStatus) -> Result<()> {
+        sqlx::query("UPDATE bangumi_match_candidate SET status = ? WHERE id = ?")
+            .bind(status)
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// 接受候选，并拒绝该番剧其余待确认的候选
+    pub async fn accept(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Sqlite>,
+        candidate: &BangumiMatchCandidate,
+    ) -> Result<()> {
+        sqlx::query("UPDATE bangumi_match_candidate SET status = ? WHERE id = ?")
+            .bind(MatchCandidateStatus::Accepted)
+            .bind(candidate.id)
+            .execute(&mut **tx)
+            .await?;
+        sqlx::query(
+            "UPDATE bangumi_match_candidate SET status = ? WHERE mikan_id = ? AND status = ? AND id != ?",
+        )
+        .bind(MatchCandidateStatus::Rejected)
+        .bind(candidate.mikan_id)
+        .bind(MatchCandidateStatus::Pending)
+        .bind(candidate.id)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_by_pair(
+        &self,
+        mikan_id: i64,
+        bangumi_id: i64,
+    ) -> Result<Option<BangumiMatchCandidate>> {
+        Ok(sqlx::query_as::<_, BangumiMatchCandidate>(
+            "SELECT * FROM bangumi_match_candidate WHERE mikan_id = ? AND bangumi_id = ?",
+        )
+        .bind(mikan_id)
+        .bind(bangumi_id)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+}
+
+#[async_trait]
+impl<'a> Repository<BangumiMatchCandidate, i64> for BangumiMatchRepository<'a> {
+    /// 已存在的候选保留原状态，避免被拒绝的候选再次出现；置信度更新为最新结果
+    async fn create(&self, candidate: &BangumiMatchCandidate) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO bangumi_match_candidate (mikan_id, bangumi_id, name, name_cn, air_date, confidence, status, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(mikan_id, bangumi_id) DO UPDATE SET confidence = excluded.confidence",
+        )
+        .bind(candidate.mikan_id)
+        .bind(candidate.bangumi_id)
+        .bind(&candidate.name)
+        .bind(&candidate.name_cn)
+        .bind(&candidate.air_date)
+        .bind(candidate.confidence)
+        .bind(&candidate.status)
+        .bind(candidate.created_at)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<BangumiMatchCandidate>> {
+        Ok(sqlx::query_as::<_, BangumiMatchCandidate>(
+            "SELECT * FROM bangumi_match_candidate WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<BangumiMatchCandidate>> {
+        let query = if limit > 0 {
+            "SELECT * FROM bangumi_match_candidate ORDER BY id DESC LIMIT ? OFFSET ?"
+        } else {
+            "SELECT * FROM bangumi_match_candidate ORDER BY id DESC LIMIT -1 OFFSET 0"
+        };
+        Ok(sqlx::query_as::<_, BangumiMatchCandidate>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    async fn update(&self, candidate: &BangumiMatchCandidate) -> Result<()> {
+        sqlx::query(
+            "UPDATE bangumi_match_candidate SET name = ?, name_cn = ?, air_date = ?, confidence = ?, status = ? WHERE id = ?",
+        )
+        .bind(&candidate.name)
+        .bind(&candidate.name_cn)
+        .bind(&candidate.air_date)
+        .bind(candidate.confidence)
+        .bind(&candidate.status)
+        .bind(candidate.id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM bangumi_match_candidate WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use crate::error::Result;
 use crate::models::{CrawlerTask, CrawlerTaskStatus, CrawlerTaskType};
 use crate::repositories::base::Repository;
 use async_trait::async_trait;
-use sqlx::SqlitePool;
+use sqlx::{QueryBuilder, SqlitePool, Transaction};
 
 pub struct CrawlerTaskRepository<'a> {
     pool: &'a SqlitePool,
@@ -52,20 +52,79 @@ impl<'a> CrawlerTaskRepository<'a> {
             .await?)
     }
 
-    // 批量将Running状态的任务标记为Failed，并写入错误信息
-    pub async fn mark_all_running_as_failed(&self, error_message: &str) -> Result<u64> {
-        let now = chrono::Utc::now().timestamp_millis();
+    /// 将所有Running任务重新放回队列，由worker从抓取边界继续
+    pub async fn requeue_running_tasks(&self) -> Result<u64> {
         let result = sqlx::query(
-            "UPDATE crawler_task SET status = ?, completed_at = ?, error_message = ? WHERE status = ?"
+            "UPDATE crawler_task SET status = ?, completed_at = NULL, error_message = NULL WHERE status = ?"
         )
-        .bind(CrawlerTaskStatus::Failed)
-        .bind(now)
-        .bind(error_message)
+        .bind(CrawlerTaskStatus::Pending)
         .bind(CrawlerTaskStatus::Running)
         .execute(self.pool)
         .await?;
         Ok(result.rows_affected())
     }
+
+    // 抓取边界：任务待抓取的详情页URL及完成状态
+
+    /// 保存任务的详情页URL列表，已存在的URL保持原完成状态
+    pub async fn save_frontier(&self, task_id: i64, urls: &[String]) -> Result<()> {
+        if urls.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        for chunk in urls.chunks(500) {
+            let mut builder = QueryBuilder::new(
+                "INSERT OR IGNORE INTO crawler_task_frontier (task_id, url, done) ",
+            );
+            builder.push_values(chunk, |mut b, url| {
+                b.push_bind(task_id).push_bind(url).push_bind(false);
+            });
+            builder.build().execute(&mut *tx).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 返回 (已完成数, 总数)
+    pub async fn frontier_progress(&self, task_id: i64) -> Result<(i64, i64)> {
+        Ok(sqlx::query_as(
+            "SELECT COALESCE(SUM(done), 0), COUNT(*) FROM crawler_task_frontier WHERE task_id = ?",
+        )
+        .bind(task_id)
+        .fetch_one(self.pool)
+        .await?)
+    }
+
+    pub async fn pending_frontier(&self, task_id: i64) -> Result<Vec<String>> {
+        Ok(sqlx::query_scalar(
+            "SELECT url FROM crawler_task_frontier WHERE task_id = ? AND done = 0 ORDER BY id",
+        )
+        .bind(task_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 与抓取数据在同一事务中标记详情页完成
+    pub async fn mark_frontier_done(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Sqlite>,
+        task_id: i64,
+        urls: &[String],
+    ) -> Result<()> {
+        for chunk in urls.chunks(500) {
+            let mut builder =
+                QueryBuilder::new("UPDATE crawler_task_frontier SET done = 1 WHERE task_id = ");
+            builder.push_bind(task_id);
+            builder.push(" AND url IN (");
+            let mut separated = builder.separated(", ");
+            for url in chunk {
+                separated.push_bind(url);
+            }
+            separated.push_unseparated(")");
+            builder.build().execute(&mut **tx).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -145,3 +204,72 @@ impl<'a> Repository<CrawlerTask, i64> for CrawlerTaskRepository<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        pool
+    }
+
+    #[tokio::test]
+    async fn frontier_resumes_from_pending_urls() {
+        let pool = test_pool().await;
+        let repo = CrawlerTaskRepository::new(&pool);
+        repo.create(&CrawlerTask {
+            id: None,
+            task_type: CrawlerTaskType::Manual,
+            status: CrawlerTaskStatus::Running,
+            parameters: None,
+            result_summary: None,
+            created_at: Some(0),
+            started_at: Some(0),
+            completed_at: None,
+            error_message: None,
+            percentage: None,
+            processed_items: None,
+            total_items: None,
+            processing_speed: None,
+            estimated_remaining: None,
+        })
+        .await
+        .unwrap();
+        let task_id = 1;
+
+        let urls: Vec<String> = (1..=4)
+            .map(|i| format!("https://example.org/{}", i))
+            .collect();
+        repo.save_frontier(task_id, &urls).await.unwrap();
+        let mut tx = pool.begin().await.unwrap();
+        repo.mark_frontier_done(&mut tx, task_id, &urls[..2])
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+        // 再次保存不会重置已完成的URL
+        repo.save_frontier(task_id, &urls).await.unwrap();
+
+        assert_eq!(repo.frontier_progress(task_id).await.unwrap(), (2, 4));
+        assert_eq!(repo.pending_frontier(task_id).await.unwrap(), urls[2..]);
+
+        // 未提交的事务不标记完成
+        let mut tx = pool.begin().await.unwrap();
+        repo.mark_frontier_done(&mut tx, task_id, &urls[2..3])
+            .await
+            .unwrap();
+        tx.rollback().await.unwrap();
+        assert_eq!(repo.pending_frontier(task_id).await.unwrap(), urls[2..]);
+
+        assert_eq!(repo.requeue_running_tasks().await.unwrap(), 1);
+        let task = repo.get_by_id(task_id).await.unwrap().unwrap();
+        assert_eq!(task.status, CrawlerTaskStatus::Pending);
+        assert_eq!(task.error_message, None);
+    }
+}
@@ -1,7 +1,12 @@
 pub mod anime;
+pub mod anime_alias;
+pub mod anime_mapping;
+pub mod bangumi_match;
 pub mod base;
 pub mod crawler_task;
 pub mod download_task;
+pub mod parse_quarantine;
 pub mod resource;
 pub mod subscription;
 pub mod subtitle_group;
+pub mod torrent_metadata;
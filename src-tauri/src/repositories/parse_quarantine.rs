@@ -0,0 +1,102 @@
+use crate::error::Result;
+use crate::models::ParseQuarantine;
+use crate::repositories::base::Repository;
+use async_trait::async_trait;
+use sqlx::SqlitePool;
+
+pub struct ParseQuarantineRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> ParseQuarantineRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 更新重新解析后的隔离原因
+    pub async fn update_reason(&self, id: i64, reason: &str) -> Result<()> {
+        sqlx::query("UPDATE parse_quarantine SET reason = ? WHERE id = ?")
+            .bind(reason)
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a> Repository<ParseQuarantine, i64> for ParseQuarantineRepository<'a> {
+    /// 同一页面再次被隔离时覆盖为最新的页面内容
+    async fn create(&self, page: &ParseQuarantine) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO parse_quarantine (task_id, source, page_kind, url, mikan_id, reason, raw_html, created_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(url) DO UPDATE SET
+                task_id = excluded.task_id,
+                source = excluded.source,
+                page_kind = excluded.page_kind,
+                mikan_id = excluded.mikan_id,
+                reason = excluded.reason,
+                raw_html = excluded.raw_html,
+                created_at = excluded.created_at",
+        )
+        .bind(page.task_id)
+        .bind(&page.source)
+        .bind(&page.page_kind)
+        .bind(&page.url)
+        .bind(page.mikan_id)
+        .bind(&page.reason)
+        .bind(&page.raw_html)
+        .bind(page.created_at)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, id: i64) -> Result<Option<ParseQuarantine>> {
+        Ok(
+            sqlx::query_as::<_, ParseQuarantine>("SELECT * FROM parse_quarantine WHERE id = ?")
+                .bind(id)
+                .fetch_optional(self.pool)
+                .await?,
+        )
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<ParseQuarantine>> {
+        let query = if limit > 0 {
+            "SELECT * FROM parse_quarantine ORDER BY created_at DESC LIMIT ? OFFSET ?"
+        } else {
+            "SELECT * FROM parse_quarantine ORDER BY created_at DESC LIMIT -1 OFFSET 0"
+        };
+        Ok(sqlx::query_as::<_, ParseQuarantine>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    async fn update(&self, page: &ParseQuarantine) -> Result<()> {
+        sqlx::query(
+            "UPDATE parse_quarantine SET task_id = ?, source = ?, page_kind = ?, url = ?, mikan_id = ?, reason = ?, raw_html = ? WHERE id = ?",
+        )
+        .bind(page.task_id)
+        .bind(&page.source)
+        .bind(&page.page_kind)
+        .bind(&page.url)
+        .bind(page.mikan_id)
+        .bind(&page.reason)
+        .bind(&page.raw_html)
+        .bind(page.id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM parse_quarantine WHERE id = ?")
+            .bind(id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
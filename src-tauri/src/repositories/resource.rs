@@ -1,8 +1,71 @@
 use crate::error::Result;
-use crate::models::{EpisodeResourceCount, Resource};
+use crate::models::{
+    EpisodeKind, EpisodeMapping, EpisodeResourceCount, Resource, SubtitleDelivery,
+};
 use crate::repositories::base::Repository;
 use async_trait::async_trait;
 use sqlx::{QueryBuilder, SqlitePool, Transaction};
+use std::collections::HashSet;
+
+// 以 CTE 形式传入各番剧的季度与集数偏移
+fn push_mapping_cte(builder: &mut QueryBuilder<'_, sqlx::Sqlite>, mappings: &[EpisodeMapping]) {
+    builder.push("WITH RECURSIVE m(mikan_id, season, episode_offset) AS (VALUES ");
+    for (i, mapping) in mappings.iter().enumerate() {
+        if i > 0 {
+            builder.push(", ");
+        }
+        builder
+            .push("(")
+            .push_bind(mapping.mikan_id)
+            .push(", ")
+            .push_bind(mapping.season)
+            .push(", ")
+            .push_bind(mapping.episode_offset)
+            .push(")");
+    }
+    builder.push(")");
+}
+
+/// 资源筛选条件，均为空时返回全部资源
+#[derive(Debug, Clone, Default)]
+pub struct ResourceFilter {
+    pub resolution: Option<String>,
+    // Bangumi 的剧集类型与集数
+    pub episode: Option<(EpisodeKind, f64)>,
+    pub subtitle_type: Option<String>,
+    // 需同时包含的字幕语言代码，如 ["chs"]
+    pub subtitle_languages: Vec<String>,
+    pub subtitle_delivery: Option<SubtitleDelivery>,
+    pub audio_language: Option<String>,
+    pub video_codec: Option<String>,
+    pub bit_depth: Option<i32>,
+    pub hdr: Option<String>,
+    pub audio_codec: Option<String>,
+    pub release_source: Option<String>,
+    pub platform: Option<String>,
+    pub container: Option<String>,
+    // 是否包含已被更高版本取代的资源
+    pub include_superseded: bool,
+}
+
+// 修订版本关联：同一字幕组、同一集且画质、来源、编码与字幕均相同的资源中，
+// 低版本的 superseded_by 指向最高版本（同版本取最新发布），调用方追加 WHERE 范围
+const LINK_REVISIONS: &str = "UPDATE resource SET superseded_by = (SELECT n.id FROM resource n \
+     WHERE n.mikan_id = resource.mikan_id \
+     AND n.subtitle_group_id = resource.subtitle_group_id \
+     AND n.episode_kind = resource.episode_kind \
+     AND n.episode_sort = resource.episode_sort \
+     AND n.episode_end IS resource.episode_end \
+     AND n.resolution IS resource.resolution \
+     AND n.release_source IS resource.release_source \
+     AND n.video_codec IS resource.video_codec \
+     AND n.subtitle_type IS resource.subtitle_type \
+     AND n.subtitle_languages IS resource.subtitle_languages \
+     AND COALESCE(n.version, 1) > COALESCE(resource.version, 1) \
+     ORDER BY COALESCE(n.version, 1) DESC, n.release_date DESC LIMIT 1) WHERE ";
+
+const MAPPING_JOIN: &str = " FROM resource r JOIN m ON r.mikan_id = m.mikan_id \
+     AND (m.season IS NULL OR r.season = m.season)";
 
 pub struct ResourceRepository<'a> {
     pool: &'a SqlitePool,
@@ -14,32 +77,90 @@ impl<'a> ResourceRepository<'a> {
     }
 
     // ResourceRepository 特有方法
+    /// 按映射筛选资源，偏移后落在第1集之前的正片不返回
     pub async fn filter(
         &self,
-        mikan_id: i64,
-        resolution: Option<String>,
-        episode_number: Option<i32>,
-        subtitle_type: Option<String>,
+        mappings: &[EpisodeMapping],
+        filter: &ResourceFilter,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Resource>> {
-        let mut builder = QueryBuilder::new("SELECT * FROM resource WHERE mikan_id = ");
-        builder.push_bind(mikan_id);
+        if mappings.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut builder = QueryBuilder::new("");
+        push_mapping_cte(&mut builder, mappings);
+        builder.push(" SELECT r.*");
+        builder.push(MAPPING_JOIN);
+        builder.push(
+            " WHERE (m.episode_offset = 0 OR r.episode_number IS NULL \
+             OR COALESCE(r.episode_end, r.episode_number) > m.episode_offset)",
+        );
 
-        if let Some(res) = resolution {
-            builder.push(" AND resolution = ");
-            builder.push_bind(res);
+        match filter.episode {
+            Some((kind, sort)) if kind.is_main() && sort.fract() == 0.0 => {
+                // 合集资源覆盖其范围内的每一集
+                let ep = sort as i32;
+                builder.push(" AND r.episode_kind IN (");
+                builder.push_bind(EpisodeKind::Main);
+                builder.push(", ");
+                builder.push_bind(EpisodeKind::Movie);
+                builder.push(") AND r.episode_number - m.episode_offset <= ");
+                builder.push_bind(ep);
+                builder
+                    .push(" AND COALESCE(r.episode_end, r.episode_number) - m.episode_offset >= ");
+                builder.push_bind(ep);
+            }
+            Some((kind, sort)) if kind.is_main() => {
+                // 小数集数（如13.5）只记录在 episode_sort
+                builder.push(" AND r.episode_kind = ");
+                builder.push_bind(EpisodeKind::Main);
+                builder
+                    .push(" AND r.episode_number IS NULL AND r.episode_sort - m.episode_offset = ");
+                builder.push_bind(sort);
+            }
+            Some((kind, sort)) => {
+                builder.push(" AND r.episode_kind = ");
+                builder.push_bind(kind);
+                builder.push(" AND r.episode_sort = ");
+                builder.push_bind(sort);
+            }
+            None => {}
+        }
+        let exact = [
+            ("resolution", &filter.resolution),
+            ("subtitle_type", &filter.subtitle_type),
+            ("audio_language", &filter.audio_language),
+            ("video_codec", &filter.video_codec),
+            ("hdr", &filter.hdr),
+            ("audio_codec", &filter.audio_codec),
+            ("release_source", &filter.release_source),
+            ("platform", &filter.platform),
+            ("container", &filter.container),
+        ];
+        for (column, value) in exact {
+            if let Some(value) = value {
+                builder.push(format!(" AND r.{} = ", column));
+                builder.push_bind(value.clone());
+            }
+        }
+        if let Some(bit_depth) = filter.bit_depth {
+            builder.push(" AND r.bit_depth = ");
+            builder.push_bind(bit_depth);
         }
-        if let Some(ep) = episode_number {
-            builder.push(" AND episode_number = ");
-            builder.push_bind(ep);
+        for language in &filter.subtitle_languages {
+            builder.push(" AND (',' || r.subtitle_languages || ',') LIKE ");
+            builder.push_bind(format!("%,{},%", language));
         }
-        if let Some(st) = subtitle_type {
-            builder.push(" AND subtitle_type = ");
-            builder.push_bind(st);
+        if let Some(delivery) = filter.subtitle_delivery {
+            builder.push(" AND r.subtitle_delivery = ");
+            builder.push_bind(delivery);
+        }
+        if !filter.include_superseded {
+            builder.push(" AND r.superseded_by IS NULL");
         }
 
-        builder.push(" ORDER BY release_date DESC");
+        builder.push(" ORDER BY r.release_date DESC");
 
         if limit > 0 {
             builder.push(" LIMIT ");
@@ -54,13 +175,95 @@ impl<'a> ResourceRepository<'a> {
         Ok(builder.build_query_as().fetch_all(self.pool).await?)
     }
 
-    pub async fn count_by_episode(&self, mikan_id: i64) -> Result<Vec<EpisodeResourceCount>> {
-        Ok(sqlx::query_as::<_, EpisodeResourceCount>(
-            "SELECT episode_number, COUNT(*) as resource_count FROM resource WHERE mikan_id = ? AND episode_number IS NOT NULL GROUP BY episode_number ORDER BY episode_number",
-        )
-        .bind(mikan_id)
-        .fetch_all(self.pool)
-        .await?)
+    /// 按换算后的 Bangumi 集数统计某类剧集的资源数，合集资源计入其范围内的每一集
+    pub async fn count_by_episode(
+        &self,
+        mappings: &[EpisodeMapping],
+        kind: EpisodeKind,
+    ) -> Result<Vec<EpisodeResourceCount>> {
+        if mappings.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut builder = QueryBuilder::new("");
+        push_mapping_cte(&mut builder, mappings);
+        if !kind.is_main() {
+            builder.push(
+                " SELECT CAST(r.episode_sort AS REAL) AS episode_sort, COUNT(*) AS resource_count",
+            );
+            builder.push(MAPPING_JOIN);
+            builder.push(" WHERE r.episode_kind = ");
+            builder.push_bind(kind);
+            builder.push(
+                " AND r.episode_sort IS NOT NULL GROUP BY r.episode_sort ORDER BY r.episode_sort",
+            );
+            return Ok(builder.build_query_as().fetch_all(self.pool).await?);
+        }
+
+        builder.push(
+            ", covered(episode_number, episode_end, episode_offset) AS (\
+             SELECT r.episode_number - m.episode_offset, \
+             COALESCE(r.episode_end, r.episode_number) - m.episode_offset, m.episode_offset",
+        );
+        builder.push(MAPPING_JOIN);
+        builder.push(" WHERE r.episode_number IS NOT NULL AND r.episode_kind IN (");
+        builder.push_bind(EpisodeKind::Main);
+        builder.push(", ");
+        builder.push_bind(EpisodeKind::Movie);
+        builder.push(
+            ") UNION ALL SELECT episode_number + 1, episode_end, episode_offset FROM covered \
+             WHERE episode_number < episode_end) \
+             SELECT CAST(episode_number AS REAL) AS episode_sort, COUNT(*) AS resource_count \
+             FROM covered WHERE episode_offset = 0 OR episode_number > 0 GROUP BY episode_number \
+             UNION ALL SELECT r.episode_sort - m.episode_offset AS episode_sort, COUNT(*) AS resource_count",
+        );
+        // 小数集数的正片，如13.5
+        builder.push(MAPPING_JOIN);
+        builder.push(" WHERE r.episode_kind = ");
+        builder.push_bind(EpisodeKind::Main);
+        builder.push(
+            " AND r.episode_number IS NULL AND r.episode_sort IS NOT NULL \
+             AND (m.episode_offset = 0 OR r.episode_sort > m.episode_offset) \
+             GROUP BY 1 ORDER BY episode_sort",
+        );
+        Ok(builder.build_query_as().fetch_all(self.pool).await?)
+    }
+
+    /// 返回给定magnet_hash中已入库的部分，用于增量抓取
+    pub async fn existing_hashes(&self, hashes: &[String]) -> Result<HashSet<String>> {
+        if hashes.is_empty() {
+            return Ok(HashSet::new());
+        }
+        let mut builder =
+            QueryBuilder::new("SELECT magnet_hash FROM resource WHERE magnet_hash IN (");
+        let mut separated = builder.separated(", ");
+        for hash in hashes {
+            separated.push_bind(hash);
+        }
+        separated.push_unseparated(")");
+        let rows: Vec<String> = builder.build_query_scalar().fetch_all(self.pool).await?;
+        Ok(rows.into_iter().collect())
+    }
+
+    /// 批量更新已入库资源的做种/下载人数
+    pub async fn update_health(&self, health: &[(String, Option<i64>, Option<i64>)]) -> Result<()> {
+        if health.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut tx = self.pool.begin().await?;
+        for (hash, seeders, leechers) in health {
+            sqlx::query(
+                "UPDATE resource SET seeders = ?, leechers = ?, updated_at = ? WHERE magnet_hash = ?",
+            )
+            .bind(seeders)
+            .bind(leechers)
+            .bind(now)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
     }
 
     pub async fn insert_many_resources(
@@ -73,7 +276,7 @@ impl<'a> ResourceRepository<'a> {
             return Ok(());
         }
         let mut builder = QueryBuilder::new(
-            "INSERT INTO resource (mikan_id, subtitle_group_id, episode_number, title, file_size, resolution, subtitle_type, magnet_url, torrent_url, play_url, magnet_hash, release_date, created_at, updated_at) "
+            "INSERT INTO resource (mikan_id, subtitle_group_id, episode_number, episode_end, season, episode_kind, episode_sort, version, title, file_size, resolution, subtitle_type, subtitle_languages, subtitle_delivery, audio_language, video_codec, bit_depth, hdr, audio_codec, release_source, platform, container, parser_version, magnet_url, torrent_url, play_url, magnet_hash, release_date, created_at, updated_at, source, seeders, leechers, size_bytes) "
         );
         builder.push("VALUES ");
         for (i, resource) in resources.iter().enumerate() {
@@ -88,6 +291,16 @@ impl<'a> ResourceRepository<'a> {
                 .push(", ")
                 .push_bind(&resource.episode_number)
                 .push(", ")
+                .push_bind(resource.episode_end)
+                .push(", ")
+                .push_bind(resource.season)
+                .push(", ")
+                .push_bind(resource.episode_kind)
+                .push(", ")
+                .push_bind(resource.episode_sort)
+                .push(", ")
+                .push_bind(resource.version)
+                .push(", ")
                 .push_bind(&resource.title)
                 .push(", ")
                 .push_bind(&resource.file_size)
@@ -96,6 +309,28 @@ impl<'a> ResourceRepository<'a> {
                 .push(", ")
                 .push_bind(&resource.subtitle_type)
                 .push(", ")
+                .push_bind(&resource.subtitle_languages)
+                .push(", ")
+                .push_bind(resource.subtitle_delivery)
+                .push(", ")
+                .push_bind(&resource.audio_language)
+                .push(", ")
+                .push_bind(&resource.video_codec)
+                .push(", ")
+                .push_bind(resource.bit_depth)
+                .push(", ")
+                .push_bind(&resource.hdr)
+                .push(", ")
+                .push_bind(&resource.audio_codec)
+                .push(", ")
+                .push_bind(&resource.release_source)
+                .push(", ")
+                .push_bind(&resource.platform)
+                .push(", ")
+                .push_bind(&resource.container)
+                .push(", ")
+                .push_bind(resource.parser_version)
+                .push(", ")
                 .push_bind(&resource.magnet_url)
                 .push(", ")
                 .push_bind(&resource.torrent_url)
@@ -109,12 +344,167 @@ impl<'a> ResourceRepository<'a> {
                 .push_bind(&resource.created_at)
                 .push(", ")
                 .push_bind(&resource.updated_at)
+                .push(", ")
+                .push_bind(&resource.source)
+                .push(", ")
+                .push_bind(resource.seeders)
+                .push(", ")
+                .push_bind(resource.leechers)
+                .push(", ")
+                .push_bind(resource.size_bytes)
                 .push(")");
         }
         builder.push(
-            " ON CONFLICT(magnet_hash) DO UPDATE SET             mikan_id = excluded.mikan_id,            subtitle_group_id = excluded.subtitle_group_id,            episode_number = excluded.episode_number,            title = excluded.title,            file_size = excluded.file_size,            resolution = excluded.resolution,            subtitle_type = excluded.subtitle_type,            magnet_url = excluded.magnet_url,            torrent_url = excluded.torrent_url,            release_date = excluded.release_date,            updated_at = excluded.updated_at",
+            " ON CONFLICT(magnet_hash) DO UPDATE SET             mikan_id = excluded.mikan_id,            subtitle_group_id = excluded.subtitle_group_id,            episode_number = excluded.episode_number, episode_end = excluded.episode_end, season = excluded.season, episode_kind = excluded.episode_kind, episode_sort = excluded.episode_sort, version = excluded.version,            title = excluded.title,            file_size = excluded.file_size,            resolution = excluded.resolution,            subtitle_type = excluded.subtitle_type, subtitle_languages = excluded.subtitle_languages, subtitle_delivery = excluded.subtitle_delivery, audio_language = excluded.audio_language, video_codec = excluded.video_codec, bit_depth = excluded.bit_depth, hdr = excluded.hdr, audio_codec = excluded.audio_codec, release_source = excluded.release_source, platform = excluded.platform, container = excluded.container, parser_version = excluded.parser_version,            magnet_url = excluded.magnet_url,            torrent_url = excluded.torrent_url,            release_date = excluded.release_date,            updated_at = excluded.updated_at,            source = excluded.source,            seeders = COALESCE(excluded.seeders, seeders),            leechers = COALESCE(excluded.leechers, leechers),            size_bytes = COALESCE(excluded.size_bytes, size_bytes)",
         );
         builder.build().execute(&mut **tx).await?;
+
+        // 只重新关联本次写入涉及的（番剧、字幕组、集数）
+        let mut keys: Vec<(i64, i64, f64)> = resources
+            .iter()
+            .filter_map(|r| Some((r.mikan_id, r.subtitle_group_id, r.episode_sort?)))
+            .collect();
+        keys.sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        keys.dedup();
+        self.link_revisions(tx, &keys).await
+    }
+
+    /// 按ID顺序分批读取资源，after_id 为上一批最后一条的ID
+    pub async fn list_after(&self, after_id: i64, limit: i64) -> Result<Vec<Resource>> {
+        Ok(
+            sqlx::query_as::<_, Resource>(
+                "SELECT * FROM resource WHERE id > ? ORDER BY id LIMIT ?",
+            )
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(self.pool)
+            .await?,
+        )
+    }
+
+    /// 解析器版本早于 version（或为空）的资源数
+    pub async fn count_outdated(&self, version: i32) -> Result<i64> {
+        Ok(sqlx::query_scalar(
+            "SELECT COUNT(*) FROM resource WHERE parser_version IS NULL OR parser_version < ?",
+        )
+        .bind(version)
+        .fetch_one(self.pool)
+        .await?)
+    }
+
+    /// 按ID顺序分批读取解析器版本早于 version 的资源
+    pub async fn list_outdated(
+        &self,
+        after_id: i64,
+        version: i32,
+        limit: i64,
+    ) -> Result<Vec<Resource>> {
+        Ok(sqlx::query_as::<_, Resource>(
+            "SELECT * FROM resource WHERE id > ? \
+             AND (parser_version IS NULL OR parser_version < ?) ORDER BY id LIMIT ?",
+        )
+        .bind(after_id)
+        .bind(version)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 保存重新解析标题得到的字段，并重新关联受影响番剧的修订版本
+    pub async fn save_reparsed(&self, resources: &[Resource]) -> Result<()> {
+        if resources.is_empty() {
+            return Ok(());
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut tx = self.pool.begin().await?;
+        for resource in resources {
+            sqlx::query(
+                "UPDATE resource SET episode_number = ?, episode_end = ?, season = ?, \
+                 episode_kind = ?, episode_sort = ?, version = ?, resolution = ?, \
+                 subtitle_type = ?, subtitle_languages = ?, subtitle_delivery = ?, \
+                 audio_language = ?, video_codec = ?, bit_depth = ?, hdr = ?, audio_codec = ?, \
+                 release_source = ?, platform = ?, container = ?, parser_version = ?, \
+                 updated_at = ? WHERE id = ?",
+            )
+            .bind(resource.episode_number)
+            .bind(resource.episode_end)
+            .bind(resource.season)
+            .bind(resource.episode_kind)
+            .bind(resource.episode_sort)
+            .bind(resource.version)
+            .bind(&resource.resolution)
+            .bind(&resource.subtitle_type)
+            .bind(&resource.subtitle_languages)
+            .bind(resource.subtitle_delivery)
+            .bind(&resource.audio_language)
+            .bind(&resource.video_codec)
+            .bind(resource.bit_depth)
+            .bind(&resource.hdr)
+            .bind(&resource.audio_codec)
+            .bind(&resource.release_source)
+            .bind(&resource.platform)
+            .bind(&resource.container)
+            .bind(resource.parser_version)
+            .bind(now)
+            .bind(resource.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let mut mikan_ids: Vec<i64> = resources.iter().map(|r| r.mikan_id).collect();
+        mikan_ids.sort_unstable();
+        mikan_ids.dedup();
+        // 重新解析可能改变集数等字段，按整部番剧重新关联
+        self.relink_animes(&mut tx, &mikan_ids).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 重新关联给定（番剧、字幕组、集数）下的修订版本
+    async fn link_revisions(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Sqlite>,
+        keys: &[(i64, i64, f64)],
+    ) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let mut builder = QueryBuilder::new(LINK_REVISIONS);
+        builder.push("(mikan_id, subtitle_group_id, episode_sort) IN (VALUES ");
+        for (i, (mikan_id, subtitle_group_id, episode_sort)) in keys.iter().enumerate() {
+            if i > 0 {
+                builder.push(", ");
+            }
+            builder
+                .push("(")
+                .push_bind(mikan_id)
+                .push(", ")
+                .push_bind(subtitle_group_id)
+                .push(", ")
+                .push_bind(episode_sort)
+                .push(")");
+        }
+        builder.push(")");
+        builder.build().execute(&mut **tx).await?;
+        Ok(())
+    }
+
+    /// 重新关联给定番剧下全部资源的修订版本
+    async fn relink_animes(
+        &self,
+        tx: &mut Transaction<'_, sqlx::Sqlite>,
+        mikan_ids: &[i64],
+    ) -> Result<()> {
+        if mikan_ids.is_empty() {
+            return Ok(());
+        }
+        let mut builder = QueryBuilder::new(LINK_REVISIONS);
+        builder.push("mikan_id IN (");
+        let mut separated = builder.separated(", ");
+        for mikan_id in mikan_ids {
+            separated.push_bind(mikan_id);
+        }
+        separated.push_unseparated(")");
+        builder.build().execute(&mut **tx).await?;
         Ok(())
     }
 }
@@ -123,15 +513,31 @@ impl<'a> ResourceRepository<'a> {
 impl<'a> Repository<Resource, i64> for ResourceRepository<'a> {
     async fn create(&self, resource: &Resource) -> Result<()> {
         sqlx::query(
-            "INSERT INTO resource (mikan_id, subtitle_group_id, episode_number, title, file_size, resolution, subtitle_type, magnet_url, torrent_url, play_url, magnet_hash, release_date, created_at, updated_at)             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)             ON CONFLICT(magnet_hash) DO UPDATE SET                mikan_id = excluded.mikan_id,                subtitle_group_id = excluded.subtitle_group_id,                episode_number = excluded.episode_number,                title = excluded.title,                file_size = excluded.file_size,                resolution = excluded.resolution,                subtitle_type = excluded.subtitle_type,                magnet_url = excluded.magnet_url,                torrent_url = excluded.torrent_url,                release_date = excluded.release_date,                updated_at = excluded.updated_at;",
+            "INSERT INTO resource (mikan_id, subtitle_group_id, episode_number, episode_end, season, episode_kind, episode_sort, version, title, file_size, resolution, subtitle_type, subtitle_languages, subtitle_delivery, audio_language, video_codec, bit_depth, hdr, audio_codec, release_source, platform, container, parser_version, magnet_url, torrent_url, play_url, magnet_hash, release_date, created_at, updated_at, source, seeders, leechers, size_bytes)             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)             ON CONFLICT(magnet_hash) DO UPDATE SET                mikan_id = excluded.mikan_id,                subtitle_group_id = excluded.subtitle_group_id,                episode_number = excluded.episode_number, episode_end = excluded.episode_end, season = excluded.season, episode_kind = excluded.episode_kind, episode_sort = excluded.episode_sort, version = excluded.version,                title = excluded.title,                file_size = excluded.file_size,                resolution = excluded.resolution,                subtitle_type = excluded.subtitle_type, subtitle_languages = excluded.subtitle_languages, subtitle_delivery = excluded.subtitle_delivery, audio_language = excluded.audio_language, video_codec = excluded.video_codec, bit_depth = excluded.bit_depth, hdr = excluded.hdr, audio_codec = excluded.audio_codec, release_source = excluded.release_source, platform = excluded.platform, container = excluded.container, parser_version = excluded.parser_version,                magnet_url = excluded.magnet_url,                torrent_url = excluded.torrent_url,                release_date = excluded.release_date,                updated_at = excluded.updated_at,                source = excluded.source,                seeders = COALESCE(excluded.seeders, seeders),                leechers = COALESCE(excluded.leechers, leechers),                size_bytes = COALESCE(excluded.size_bytes, size_bytes);",
         )
         .bind(resource.mikan_id)
         .bind(resource.subtitle_group_id)
         .bind(resource.episode_number)
+        .bind(resource.episode_end)
+        .bind(resource.season)
+        .bind(resource.episode_kind)
+        .bind(resource.episode_sort)
+        .bind(resource.version)
         .bind(&resource.title)
         .bind(&resource.file_size)
         .bind(&resource.resolution)
         .bind(&resource.subtitle_type)
+        .bind(&resource.subtitle_languages)
+        .bind(resource.subtitle_delivery)
+        .bind(&resource.audio_language)
+        .bind(&resource.video_codec)
+        .bind(resource.bit_depth)
+        .bind(&resource.hdr)
+        .bind(&resource.audio_codec)
+        .bind(&resource.release_source)
+        .bind(&resource.platform)
+        .bind(&resource.container)
+        .bind(resource.parser_version)
         .bind(&resource.magnet_url)
         .bind(&resource.torrent_url)
         .bind(&resource.play_url)
@@ -139,6 +545,10 @@ impl<'a> Repository<Resource, i64> for ResourceRepository<'a> {
         .bind(resource.release_date)
         .bind(resource.created_at)
         .bind(resource.updated_at)
+        .bind(&resource.source)
+        .bind(resource.seeders)
+        .bind(resource.leechers)
+        .bind(resource.size_bytes)
         .execute(self.pool)
         .await?;
         Ok(())
@@ -168,21 +578,41 @@ impl<'a> Repository<Resource, i64> for ResourceRepository<'a> {
 
     async fn update(&self, resource: &Resource) -> Result<()> {
         sqlx::query(
-            "UPDATE resource SET mikan_id = ?, subtitle_group_id = ?, episode_number = ?, title = ?, file_size = ?, resolution = ?, subtitle_type = ?, magnet_url = ?, torrent_url = ?, play_url = ?, magnet_hash = ?, release_date = ?, updated_at = ? WHERE id = ?",
+            "UPDATE resource SET mikan_id = ?, subtitle_group_id = ?, episode_number = ?, episode_end = ?, season = ?, episode_kind = ?, episode_sort = ?, version = ?, title = ?, file_size = ?, resolution = ?, subtitle_type = ?, subtitle_languages = ?, subtitle_delivery = ?, audio_language = ?, video_codec = ?, bit_depth = ?, hdr = ?, audio_codec = ?, release_source = ?, platform = ?, container = ?, parser_version = ?, magnet_url = ?, torrent_url = ?, play_url = ?, magnet_hash = ?, release_date = ?, updated_at = ?, source = ?, seeders = ?, leechers = ?, size_bytes = ? WHERE id = ?",
         )
         .bind(resource.mikan_id)
         .bind(resource.subtitle_group_id)
         .bind(resource.episode_number)
+        .bind(resource.episode_end)
+        .bind(resource.season)
+        .bind(resource.episode_kind)
+        .bind(resource.episode_sort)
+        .bind(resource.version)
         .bind(&resource.title)
         .bind(&resource.file_size)
         .bind(&resource.resolution)
         .bind(&resource.subtitle_type)
+        .bind(&resource.subtitle_languages)
+        .bind(resource.subtitle_delivery)
+        .bind(&resource.audio_language)
+        .bind(&resource.video_codec)
+        .bind(resource.bit_depth)
+        .bind(&resource.hdr)
+        .bind(&resource.audio_codec)
+        .bind(&resource.release_source)
+        .bind(&resource.platform)
+        .bind(&resource.container)
+        .bind(resource.parser_version)
         .bind(&resource.magnet_url)
         .bind(&resource.torrent_url)
         .bind(&resource.play_url)
         .bind(&resource.magnet_hash)
         .bind(resource.release_date)
         .bind(resource.updated_at)
+        .bind(&resource.source)
+        .bind(resource.seeders)
+        .bind(resource.leechers)
+        .bind(resource.size_bytes)
         .bind(resource.id)
         .execute(self.pool)
         .await?;
@@ -197,3 +627,131 @@ impl<'a> Repository<Resource, i64> for ResourceRepository<'a> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    async fn test_pool() -> SqlitePool {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO anime (mikan_id, bangumi_id, title) VALUES (1, 100, 'A'), (2, 100, 'A 2')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO subtitle_group (id, name) VALUES (1, 'G')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn resource(mikan_id: i64, hash: &str, episode: i32, episode_end: Option<i32>) -> Resource {
+        Resource {
+            mikan_id,
+            subtitle_group_id: 1,
+            episode_number: Some(episode),
+            episode_end,
+            episode_sort: Some(f64::from(episode)),
+            title: hash.to_string(),
+            magnet_hash: Some(hash.to_string()),
+            source: "mikan".to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn insert(pool: &SqlitePool, resources: &[Resource]) {
+        let repo = ResourceRepository::new(pool);
+        let mut tx = pool.begin().await.unwrap();
+        repo.insert_many_resources(&mut tx, resources)
+            .await
+            .unwrap();
+        tx.commit().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn count_by_episode_expands_batches_and_offsets() {
+        let pool = test_pool().await;
+        insert(
+            &pool,
+            &[
+                resource(1, "single", 3, None),
+                resource(1, "batch", 1, Some(12)),
+                // 第二部按续播集数发布，偏移12后为第2集
+                resource(2, "second", 14, None),
+                // 换算后不大于0的集数属于上一部
+                resource(2, "previous", 12, None),
+            ],
+        )
+        .await;
+        let mappings = [
+            EpisodeMapping {
+                mikan_id: 1,
+                season: None,
+                episode_offset: 0,
+            },
+            EpisodeMapping {
+                mikan_id: 2,
+                season: None,
+                episode_offset: 12,
+            },
+        ];
+
+        let counts = ResourceRepository::new(&pool)
+            .count_by_episode(&mappings, EpisodeKind::Main)
+            .await
+            .unwrap();
+        let counts: Vec<(f64, i64)> = counts
+            .into_iter()
+            .map(|c| (c.episode_sort, c.resource_count))
+            .collect();
+        let expected: Vec<(f64, i64)> = (1..=12)
+            .map(|ep| (f64::from(ep), if ep == 2 || ep == 3 { 2 } else { 1 }))
+            .collect();
+        assert_eq!(counts, expected);
+    }
+
+    #[tokio::test]
+    async fn revisions_link_only_matching_releases() {
+        let pool = test_pool().await;
+        let release = |hash: &str, version: i32, source: &str, codec: &str| Resource {
+            version: Some(version),
+            release_source: Some(source.to_string()),
+            video_codec: Some(codec.to_string()),
+            ..resource(1, hash, 5, None)
+        };
+        insert(&pool, &[release("web-v1", 1, "WebRip", "AVC")]).await;
+        // 分批写入同一集的其他版本，先前写入的资源也应被重新关联
+        insert(
+            &pool,
+            &[
+                release("bd-v1", 1, "BDRip", "HEVC"),
+                release("web-v2", 2, "WebRip", "AVC"),
+            ],
+        )
+        .await;
+
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT r.magnet_hash, n.magnet_hash FROM resource r \
+             LEFT JOIN resource n ON n.id = r.superseded_by ORDER BY r.magnet_hash",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("bd-v1".to_string(), None),
+                ("web-v1".to_string(), Some("web-v2".to_string())),
+                ("web-v2".to_string(), None),
+            ]
+        );
+    }
+}
@@ -33,6 +33,34 @@ impl<'a> SubtitleGroupRepository<'a> {
         Ok(q.fetch_all(self.pool).await?)
     }
 
+    /// 按名称获取字幕组，不存在时新建。
+    /// 非 Mikan 来源的字幕组没有站点ID，使用负数ID以免与 Mikan 字幕组冲突。
+    /// 返回值第二项表示是否为新建
+    pub async fn get_or_create_by_name(&self, name: &str) -> Result<(SubtitleGroup, bool)> {
+        if let Some(group) = sqlx::query_as::<_, SubtitleGroup>(
+            "SELECT * FROM subtitle_group WHERE name = ? LIMIT 1",
+        )
+        .bind(name)
+        .fetch_optional(self.pool)
+        .await?
+        {
+            return Ok((group, false));
+        }
+        let now = chrono::Utc::now().timestamp_millis();
+        // 在单条语句中分配ID，避免并发任务拿到同一个ID
+        let group = sqlx::query_as::<_, SubtitleGroup>(
+            "INSERT INTO subtitle_group (id, name, last_update, created_at)
+             SELECT MIN(COALESCE(MIN(id), 0), 0) - 1, ?, ?, ? FROM subtitle_group
+             RETURNING *",
+        )
+        .bind(name)
+        .bind(now)
+        .bind(now)
+        .fetch_one(self.pool)
+        .await?;
+        Ok((group, true))
+    }
+
     pub async fn insert_many_subtitle_groups(
         &self,
         tx: &mut Transaction<'_, sqlx::Sqlite>,
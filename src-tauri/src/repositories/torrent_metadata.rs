@@ -0,0 +1,190 @@
+use crate::core::torrent_parser::TorrentInfo;
+use crate::error::Result;
+use crate::models::{Resource, TorrentFile, TorrentMetadata, TorrentMetadataStatus};
+use crate::repositories::base::Repository;
+use async_trait::async_trait;
+use sqlx::{QueryBuilder, SqlitePool};
+
+pub struct TorrentMetadataRepository<'a> {
+    pool: &'a SqlitePool,
+}
+
+impl<'a> TorrentMetadataRepository<'a> {
+    pub fn new(pool: &'a SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// 有种子链接但尚未解析的资源，失败次数未达上限的会重试，新资源优先
+    pub async fn pending_resources(&self, max_attempts: i64, limit: i64) -> Result<Vec<Resource>> {
+        Ok(sqlx::query_as::<_, Resource>(
+            "SELECT r.* FROM resource r
+             LEFT JOIN torrent_metadata t ON t.resource_id = r.id
+             WHERE r.torrent_url IS NOT NULL AND r.torrent_url != ''
+               AND (t.resource_id IS NULL OR (t.status = ? AND t.attempts < ?))
+             ORDER BY r.release_date DESC
+             LIMIT ?",
+        )
+        .bind(TorrentMetadataStatus::Failed)
+        .bind(max_attempts)
+        .bind(limit)
+        .fetch_all(self.pool)
+        .await?)
+    }
+
+    /// 保存解析结果与文件清单，并用精确大小更新资源
+    pub async fn save_fetched(&self, resource_id: i64, info: &TorrentInfo) -> Result<()> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut tx = self.pool.begin().await?;
+        sqlx::query(
+            "INSERT INTO torrent_metadata (resource_id, status, info_hash, name, total_size, piece_length, file_count, error_message, attempts, fetched_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, NULL, 1, ?)
+             ON CONFLICT(resource_id) DO UPDATE SET
+                status = excluded.status,
+                info_hash = excluded.info_hash,
+                name = excluded.name,
+                total_size = excluded.total_size,
+                piece_length = excluded.piece_length,
+                file_count = excluded.file_count,
+                error_message = NULL,
+                attempts = torrent_metadata.attempts + 1,
+                fetched_at = excluded.fetched_at",
+        )
+        .bind(resource_id)
+        .bind(TorrentMetadataStatus::Fetched)
+        .bind(&info.info_hash)
+        .bind(&info.name)
+        .bind(info.total_size)
+        .bind(info.piece_length)
+        .bind(info.files.len() as i64)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM torrent_file WHERE resource_id = ?")
+            .bind(resource_id)
+            .execute(&mut *tx)
+            .await?;
+        // 每个文件4个参数，每批200个文件，控制在 SQLite 参数上限内
+        for chunk in info.files.chunks(200) {
+            let mut builder = QueryBuilder::new(
+                "INSERT INTO torrent_file (resource_id, file_index, path, size) ",
+            );
+            builder.push_values(chunk, |mut b, file| {
+                b.push_bind(resource_id)
+                    .push_bind(file.file_index)
+                    .push_bind(&file.path)
+                    .push_bind(file.size);
+            });
+            builder.build().execute(&mut *tx).await?;
+        }
+
+        sqlx::query("UPDATE resource SET size_bytes = ?, updated_at = ? WHERE id = ?")
+            .bind(info.total_size)
+            .bind(now)
+            .bind(resource_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn save_failed(&self, resource_id: i64, error_message: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO torrent_metadata (resource_id, status, error_message, attempts, fetched_at)
+             VALUES (?, ?, ?, 1, ?)
+             ON CONFLICT(resource_id) DO UPDATE SET
+                status = excluded.status,
+                error_message = excluded.error_message,
+                attempts = torrent_metadata.attempts + 1,
+                fetched_at = excluded.fetched_at",
+        )
+        .bind(resource_id)
+        .bind(TorrentMetadataStatus::Failed)
+        .bind(error_message)
+        .bind(chrono::Utc::now().timestamp_millis())
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_files(&self, resource_id: i64) -> Result<Vec<TorrentFile>> {
+        Ok(sqlx::query_as::<_, TorrentFile>(
+            "SELECT * FROM torrent_file WHERE resource_id = ? ORDER BY file_index",
+        )
+        .bind(resource_id)
+        .fetch_all(self.pool)
+        .await?)
+    }
+}
+
+#[async_trait]
+impl<'a> Repository<TorrentMetadata, i64> for TorrentMetadataRepository<'a> {
+    async fn create(&self, metadata: &TorrentMetadata) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO torrent_metadata (resource_id, status, info_hash, name, total_size, piece_length, file_count, error_message, attempts, fetched_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(metadata.resource_id)
+        .bind(&metadata.status)
+        .bind(&metadata.info_hash)
+        .bind(&metadata.name)
+        .bind(metadata.total_size)
+        .bind(metadata.piece_length)
+        .bind(metadata.file_count)
+        .bind(&metadata.error_message)
+        .bind(metadata.attempts)
+        .bind(metadata.fetched_at)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_by_id(&self, resource_id: i64) -> Result<Option<TorrentMetadata>> {
+        Ok(sqlx::query_as::<_, TorrentMetadata>(
+            "SELECT * FROM torrent_metadata WHERE resource_id = ?",
+        )
+        .bind(resource_id)
+        .fetch_optional(self.pool)
+        .await?)
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<TorrentMetadata>> {
+        let query = if limit > 0 {
+            "SELECT * FROM torrent_metadata ORDER BY fetched_at DESC LIMIT ? OFFSET ?"
+        } else {
+            "SELECT * FROM torrent_metadata ORDER BY fetched_at DESC LIMIT -1 OFFSET 0"
+        };
+        Ok(sqlx::query_as::<_, TorrentMetadata>(query)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(self.pool)
+            .await?)
+    }
+
+    async fn update(&self, metadata: &TorrentMetadata) -> Result<()> {
+        sqlx::query(
+            "UPDATE torrent_metadata SET status = ?, info_hash = ?, name = ?, total_size = ?, piece_length = ?, file_count = ?, error_message = ?, attempts = ?, fetched_at = ? WHERE resource_id = ?",
+        )
+        .bind(&metadata.status)
+        .bind(&metadata.info_hash)
+        .bind(&metadata.name)
+        .bind(metadata.total_size)
+        .bind(metadata.piece_length)
+        .bind(metadata.file_count)
+        .bind(&metadata.error_message)
+        .bind(metadata.attempts)
+        .bind(metadata.fetched_at)
+        .bind(metadata.resource_id)
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete(&self, resource_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM torrent_metadata WHERE resource_id = ?")
+            .bind(resource_id)
+            .execute(self.pool)
+            .await?;
+        Ok(())
+    }
+}
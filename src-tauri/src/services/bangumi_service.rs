@@ -1,25 +1,43 @@
 use crate::config::Config;
-use crate::error::{ApiError, AppError};
-use crate::types::bangumi::{BangumiEpisodesData, BangumiSubject, BangumiWeekday};
+use crate::core::http_fetcher::HttpFetcher;
+use crate::core::{text_parser, title_matcher};
+use crate::error::{ApiError, AppError, DomainError};
+use crate::models::{Anime, BangumiMatchCandidate, MatchCandidateStatus};
+use crate::repositories::{
+    anime::AnimeRepository, bangumi_match::BangumiMatchRepository, base::Repository,
+};
+use crate::types::bangumi::{
+    BangumiEpisodesData, BangumiSearchItem, BangumiSearchResponse, BangumiSubject, BangumiWeekday,
+    ReconcileSummary,
+};
 use sqlx::SqlitePool;
 use std::sync::Arc;
 
+// 置信度不低于该值且明显高于次优候选时自动关联
+const AUTO_LINK_CONFIDENCE: f64 = 0.9;
+const AUTO_LINK_MARGIN: f64 = 0.1;
+// 低于该值的搜索结果不作为候选
+const MIN_CANDIDATE_CONFIDENCE: f64 = 0.3;
+// 每部番剧最多保存的候选数
+const MAX_CANDIDATES: usize = 5;
+
 pub struct BangumiService {
     base_url: String,
-    client: reqwest::Client,
+    fetcher: HttpFetcher,
     pool: Arc<SqlitePool>,
     config: Config,
 }
 
 impl BangumiService {
     pub fn new(pool: Arc<SqlitePool>, config: Config) -> Self {
-        let client = reqwest::Client::builder()
-            .user_agent("Ikuyo-App/1.0 (https://github.com/your-repo-link)") // 添加User-Agent
-            .build()
-            .expect("reqwest client 构建失败");
+        // 与爬虫共用 HttpFetcher，录制/回放模式同样作用于 Bangumi API
+        let mut http = config.http();
+        if http.user_agent.is_none() {
+            http.user_agent = Some("Ikuyo-App/1.0 (https://github.com/your-repo-link)".to_string());
+        }
         Self {
             base_url: "https://api.bgm.tv".to_string(),
-            client,
+            fetcher: HttpFetcher::new(&http),
             pool,
             config,
         }
@@ -52,11 +70,11 @@ impl BangumiService {
 
         // 2. 请求API
         let url = format!("{}/calendar", self.base_url);
-        let response = self.client.get(&url).send().await;
+        let response = self.fetcher.get(&url).await;
         match response {
             Ok(resp) => {
-                if resp.status().is_success() {
-                    let data: Vec<BangumiWeekday> = resp.json().await?;
+                if resp.is_success() {
+                    let data: Vec<BangumiWeekday> = serde_json::from_str(&resp.body)?;
                     let content = serde_json::to_string(&data)?;
                     let updated_at = Utc::now().timestamp();
                     let _ = sqlx::query(
@@ -79,7 +97,7 @@ impl BangumiService {
                     }
                     Err(AppError::Api(ApiError::Response(format!(
                         "请求失败: {}",
-                        resp.status()
+                        resp.status
                     ))))
                 }
             }
@@ -90,7 +108,7 @@ impl BangumiService {
                     let data: Vec<BangumiWeekday> = serde_json::from_str(&content)?;
                     return Ok(data);
                 }
-                Err(e.into())
+                Err(e)
             }
         }
     }
@@ -122,8 +140,7 @@ impl BangumiService {
 
         // 2. 请求API
         let url = format!("{}/v0/subjects/{}", self.base_url, id);
-        let response = self.client.get(&url).send().await?;
-        let data: BangumiSubject = response.json().await?;
+        let data: BangumiSubject = serde_json::from_str(&self.fetcher.fetch(&url).await?)?;
         let content = serde_json::to_string(&data)?;
         let updated_at = Utc::now().timestamp();
         // 3. 写入/更新缓存
@@ -196,11 +213,11 @@ impl BangumiService {
         if !params.is_empty() {
             url = format!("{}?{}", url, params.join("&"));
         }
-        let response = self.client.get(&url).send().await;
+        let response = self.fetcher.get(&url).await;
         match response {
             Ok(resp) => {
-                if resp.status().is_success() {
-                    let data: BangumiEpisodesData = resp.json().await?;
+                if resp.is_success() {
+                    let data: BangumiEpisodesData = serde_json::from_str(&resp.body)?;
                     let content = serde_json::to_string(&data)?;
                     let updated_at = Utc::now().timestamp();
                     let _ = sqlx::query(
@@ -224,7 +241,7 @@ impl BangumiService {
                     }
                     Err(AppError::Api(ApiError::Response(format!(
                         "请求失败: {}",
-                        resp.status()
+                        resp.status
                     ))))
                 }
             }
@@ -235,7 +252,7 @@ impl BangumiService {
                     let data: BangumiEpisodesData = serde_json::from_str(&content)?;
                     return Ok(data);
                 }
-                Err(e.into())
+                Err(e)
             }
         }
     }
@@ -244,9 +261,7 @@ impl BangumiService {
     pub async fn aggregate_resources(
         &self,
         bangumi_id: i64,
-        episode: Option<i64>,
-        resolution: Option<String>,
-        subtitle_type: Option<String>,
+        filter: crate::repositories::resource::ResourceFilter,
         limit: Option<i64>,
         offset: Option<i64>,
     ) -> Result<Option<crate::types::bangumi::EpisodeResourcesData>, AppError> {
@@ -259,17 +274,17 @@ impl BangumiService {
         let resource_repo = ResourceRepository::new(&self.pool);
         let subtitle_group_repo = SubtitleGroupRepository::new(&self.pool);
 
-        let anime = anime_repo.get_by_bangumi_id(bangumi_id).await?;
-        if let Some(anime) = anime {
+        // 一个 Bangumi 条目可能对应多个 Mikan 番剧，见 anime_bangumi_mapping
+        let mappings = anime_repo
+            .episode_mappings_by_bangumi_id(bangumi_id)
+            .await?;
+        if !mappings.is_empty() {
+            let offsets: HashMap<i64, i32> = mappings
+                .iter()
+                .map(|m| (m.mikan_id, m.episode_offset))
+                .collect();
             let resources = resource_repo
-                .filter(
-                    anime.mikan_id,
-                    resolution,
-                    episode.map(|e| e as i32),
-                    subtitle_type,
-                    limit.unwrap_or(0),
-                    offset.unwrap_or(0),
-                )
+                .filter(&mappings, &filter, limit.unwrap_or(0), offset.unwrap_or(0))
                 .await?;
 
             // 收集所有 group_id
@@ -301,19 +316,51 @@ impl BangumiService {
                             resources: Vec::new(),
                         });
 
+                // 换算为 Bangumi 集数
+                let episode_offset = offsets.get(&res.mikan_id).copied().unwrap_or(0);
                 entry.resource_count += 1;
                 entry.resources.push(EpisodeResource {
                     id: res.id.unwrap_or_default(),
-                    episode_number: res.episode_number.unwrap_or_default() as i64,
+                    episode_number: res
+                        .episode_number
+                        .map_or(0, |e| (e - episode_offset) as i64),
+                    episode_end: res.episode_end.map(|e| (e - episode_offset) as i64),
+                    version: res.version,
+                    superseded_by: res.superseded_by,
+                    episode_kind: res.episode_kind,
+                    episode_sort: res.episode_sort.map(|e| {
+                        if res.episode_kind == crate::models::EpisodeKind::Main {
+                            e - episode_offset as f64
+                        } else {
+                            e
+                        }
+                    }),
                     title: res.title,
                     resolution: res.resolution.unwrap_or_default(),
                     subtitle_type: res.subtitle_type.unwrap_or_default(),
+                    subtitle_languages: res
+                        .subtitle_languages
+                        .map(|l| l.split(',').map(str::to_string).collect())
+                        .unwrap_or_default(),
+                    subtitle_delivery: res.subtitle_delivery,
+                    audio_language: res.audio_language,
+                    video_codec: res.video_codec,
+                    bit_depth: res.bit_depth,
+                    hdr: res.hdr,
+                    audio_codec: res.audio_codec,
+                    release_source: res.release_source,
+                    platform: res.platform,
+                    container: res.container,
                     magnet_url: res.magnet_url.unwrap_or_default(),
                     torrent_url: res.torrent_url.unwrap_or_default(),
                     release_date: res.release_date.unwrap_or_default().to_string(),
                     size: res.file_size.unwrap_or_default(),
+                    size_bytes: res.size_bytes,
                     group_id: res.subtitle_group_id,
                     group_name,
+                    source: res.source,
+                    seeders: res.seeders,
+                    leechers: res.leechers,
                 });
             }
 
@@ -329,4 +376,206 @@ impl BangumiService {
             Ok(None)
         }
     }
+
+    /// 按关键词搜索 Bangumi 动画条目。v0 搜索接口只支持POST，这里使用旧版GET接口
+    pub async fn search_subjects(&self, keyword: &str) -> Result<Vec<BangumiSearchItem>, AppError> {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .map_err(|e| AppError::Api(ApiError::Request(e.to_string())))?;
+        url.path_segments_mut()
+            .map_err(|_| AppError::Api(ApiError::Request("无效的API地址".to_string())))?
+            .extend(["search", "subject", keyword.trim()]);
+        url.query_pairs_mut()
+            .append_pair("type", "2")
+            .append_pair("responseGroup", "small")
+            .append_pair("max_results", "10");
+        let resp = self.fetcher.get(url.as_str()).await?;
+        // 没有结果时接口返回404
+        if resp.status == 404 {
+            return Ok(Vec::new());
+        }
+        if !resp.is_success() {
+            return Err(AppError::Api(ApiError::Response(format!(
+                "请求失败: {}",
+                resp.status
+            ))));
+        }
+        let data: BangumiSearchResponse = serde_json::from_str(&resp.body)?;
+        Ok(data.list.unwrap_or_default())
+    }
+
+    /// 为缺少 bangumi_id 的番剧搜索 Bangumi 条目：高置信度的直接关联，其余保存为待确认候选
+    pub async fn reconcile_missing_ids(&self, limit: i64) -> Result<ReconcileSummary, AppError> {
+        let anime_repo = AnimeRepository::new(&self.pool);
+        let match_repo = BangumiMatchRepository::new(&self.pool);
+        let mut summary = ReconcileSummary::default();
+        for anime in anime_repo.list_missing_bangumi_id(limit).await? {
+            summary.checked += 1;
+            let items = match self.search_subjects(&anime.title).await {
+                Ok(items) => items,
+                Err(e) => {
+                    tracing::warn!("搜索番剧{}的Bangumi条目失败: {}", anime.title, e);
+                    continue;
+                }
+            };
+            let mut scored: Vec<(f64, BangumiSearchItem)> = items
+                .into_iter()
+                .map(|item| (match_confidence(&anime, &item), item))
+                .filter(|(confidence, _)| *confidence >= MIN_CANDIDATE_CONFIDENCE)
+                .collect();
+            scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+            scored.truncate(MAX_CANDIDATES);
+            if scored.is_empty() {
+                summary.no_candidates += 1;
+                continue;
+            }
+
+            let now = chrono::Utc::now().timestamp_millis();
+            let mut pending = 0;
+            for (confidence, item) in &scored {
+                let candidate = BangumiMatchCandidate {
+                    id: None,
+                    mikan_id: anime.mikan_id,
+                    bangumi_id: item.id,
+                    name: item.name.clone(),
+                    name_cn: Some(item.name_cn.clone()).filter(|n| !n.is_empty()),
+                    air_date: item.air_date.clone(),
+                    confidence: *confidence,
+                    status: MatchCandidateStatus::Pending,
+                    created_at: now,
+                };
+                match_repo.create(&candidate).await?;
+                // 曾被用户拒绝的候选保持拒绝状态
+                if let Some(saved) = match_repo.get_by_pair(anime.mikan_id, item.id).await? {
+                    if saved.status == MatchCandidateStatus::Pending {
+                        pending += 1;
+                    }
+                }
+            }
+
+            let (best_confidence, best) = &scored[0];
+            let runner_up = scored.get(1).map_or(0.0, |(c, _)| *c);
+            // 已被其他番剧使用的 bangumi_id 需要用户确认
+            let taken = anime_repo.get_by_bangumi_id(best.id).await?.is_some();
+            if *best_confidence >= AUTO_LINK_CONFIDENCE
+                && best_confidence - runner_up >= AUTO_LINK_MARGIN
+                && !taken
+            {
+                if let Some(candidate) = match_repo.get_by_pair(anime.mikan_id, best.id).await? {
+                    if candidate.status == MatchCandidateStatus::Pending {
+                        self.link_candidate(&candidate).await?;
+                        tracing::info!(
+                            "番剧{}自动关联Bangumi条目{}（置信度{:.2}）",
+                            anime.title,
+                            best.id,
+                            best_confidence
+                        );
+                        summary.auto_linked += 1;
+                        continue;
+                    }
+                }
+            }
+            if pending > 0 {
+                summary.proposed += 1;
+            } else {
+                summary.no_candidates += 1;
+            }
+        }
+        Ok(summary)
+    }
+
+    /// 用户确认候选：关联番剧并拒绝该番剧的其他候选。
+    /// 条目已关联到其他番剧时返回冲突，用户确认后以 allow_shared 再次提交
+    pub async fn confirm_match(
+        &self,
+        candidate_id: i64,
+        allow_shared: bool,
+    ) -> Result<BangumiMatchCandidate, AppError> {
+        let candidate = self.get_candidate(candidate_id).await?;
+        if candidate.status != MatchCandidateStatus::Pending {
+            return Err(AppError::Domain(DomainError::Conflict(format!(
+                "候选{}已处理，无法再次确认",
+                candidate_id
+            ))));
+        }
+        if !allow_shared {
+            if let Some(other) = AnimeRepository::new(&self.pool)
+                .get_by_bangumi_id(candidate.bangumi_id)
+                .await?
+                .filter(|anime| anime.mikan_id != candidate.mikan_id)
+            {
+                return Err(AppError::Domain(DomainError::Conflict(format!(
+                    "Bangumi条目{}已关联番剧{}",
+                    candidate.bangumi_id, other.title
+                ))));
+            }
+        }
+        self.link_candidate(&candidate).await?;
+        self.get_candidate(candidate_id).await
+    }
+
+    pub async fn reject_match(&self, candidate_id: i64) -> Result<BangumiMatchCandidate, AppError> {
+        let match_repo = BangumiMatchRepository::new(&self.pool);
+        self.get_candidate(candidate_id).await?;
+        match_repo
+            .set_status(candidate_id, MatchCandidateStatus::Rejected)
+            .await?;
+        self.get_candidate(candidate_id).await
+    }
+
+    async fn get_candidate(&self, candidate_id: i64) -> Result<BangumiMatchCandidate, AppError> {
+        BangumiMatchRepository::new(&self.pool)
+            .get_by_id(candidate_id)
+            .await?
+            .ok_or_else(|| {
+                AppError::Domain(DomainError::NotFound {
+                    resource_type: "bangumi_match_candidate".to_string(),
+                    resource_id: candidate_id,
+                })
+            })
+    }
+
+    async fn link_candidate(&self, candidate: &BangumiMatchCandidate) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        AnimeRepository::new(&self.pool)
+            .set_bangumi_id(&mut tx, candidate.mikan_id, candidate.bangumi_id)
+            .await?;
+        BangumiMatchRepository::new(&self.pool)
+            .accept(&mut tx, candidate)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// 候选置信度：标题相似度占 3/4，放送日期接近程度占 1/4，日期未知时按一半计
+fn match_confidence(anime: &Anime, item: &BangumiSearchItem) -> f64 {
+    let title_score = [&item.name_cn, &item.name]
+        .iter()
+        .filter(|name| !name.is_empty())
+        .flat_map(|name| {
+            let original = anime.original_title.as_deref().unwrap_or_default();
+            [
+                title_matcher::title_similarity(&anime.title, name),
+                title_matcher::title_similarity(original, name),
+            ]
+        })
+        .fold(0.0, f64::max);
+    let anime_date = anime
+        .broadcast_start
+        .as_deref()
+        .and_then(text_parser::parse_air_date);
+    let item_date = item
+        .air_date
+        .as_deref()
+        .and_then(text_parser::parse_air_date);
+    let date_score = match (anime_date, item_date) {
+        (Some(a), Some(b)) => match (a - b).num_days().abs() {
+            0..=7 => 1.0,
+            8..=31 => 0.7,
+            32..=90 => 0.4,
+            _ => 0.0,
+        },
+        _ => 0.5,
+    };
+    0.75 * title_score + 0.25 * date_score
 }
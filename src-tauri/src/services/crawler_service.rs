@@ -1,21 +1,35 @@
-use crate::core::anime_parser::AnimeParser;
-use crate::core::http_fetcher::HttpFetcher;
-use crate::core::mikan_parser::MikanParser;
+use crate::config::Config;
+use crate::core::anime_parser::{AnimeParser, FeedMapping};
+use crate::core::http_fetcher::{redact_url, FetchOutcome, HttpFetcher};
+use crate::core::source_registry::{SourceRegistry, DEFAULT_SOURCE};
+use crate::core::title_matcher::TitleMatcher;
+use crate::core::{AnimeData, FeedItem};
 use crate::error::{AppError, Result, TaskError};
-use crate::models::{Anime, CrawlerTaskStatus, Resource, SubtitleGroup};
+use crate::models::{
+    Anime, CrawlerTaskStatus, ParseQuarantine, QuarantinePageKind, Resource, SubtitleGroup,
+};
 use crate::repositories::{
-    anime::AnimeRepository, base::Repository, crawler_task::CrawlerTaskRepository,
+    anime::AnimeRepository, anime_alias::AnimeAliasRepository, base::Repository,
+    crawler_task::CrawlerTaskRepository, parse_quarantine::ParseQuarantineRepository,
     resource::ResourceRepository, subtitle_group::SubtitleGroupRepository,
 };
-use crate::types::crawler::{CrawlerMode, CrawlerTaskCreate, SeasonName};
+use crate::services::parser_service::ParserService;
+use crate::types::crawler::{
+    CrawlResultSummary, CrawlerMode, CrawlerTaskCreate, QuarantineReparseResult, SeasonName,
+    SummaryAnime, SummaryGroup,
+};
 use futures_util::stream::{self, StreamExt};
 use sqlx::SqlitePool;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio_util::sync::CancellationToken;
 
+// 发布标题中没有发布组时归入的字幕组
+const UNKNOWN_GROUP_NAME: &str = "未知字幕组";
+
 pub struct CrawlerService {
     pub pool: Arc<SqlitePool>,
+    pub config: Config,
     pub task_id: i64,
     pub anime_buffer: Vec<Anime>,
     pub subtitle_group_buffer: Vec<SubtitleGroup>,
@@ -25,14 +39,21 @@ pub struct CrawlerService {
     pub resource_hashes: HashSet<String>,
     pub processed_items: i64,
     pub total_items: i64,
+    // 已完成但尚未随缓冲区写入的详情页URL
+    frontier_done: Vec<String>,
+    // 本次任务的结果摘要，summary_base_ms 为此前运行累计的时长
+    summary: CrawlResultSummary,
+    summary_base_ms: i64,
+    run_started_at: i64,
     // 新增：任务取消信号
     cancellation_token: Option<CancellationToken>,
 }
 
 impl CrawlerService {
-    pub fn new(pool: Arc<SqlitePool>, task_id: i64) -> Self {
+    pub fn new(pool: Arc<SqlitePool>, config: Config, task_id: i64) -> Self {
         Self {
             pool,
+            config,
             task_id,
             anime_buffer: Vec::new(),
             subtitle_group_buffer: Vec::new(),
@@ -42,6 +63,10 @@ impl CrawlerService {
             resource_hashes: HashSet::new(),
             processed_items: 0,
             total_items: 0,
+            frontier_done: Vec::new(),
+            summary: CrawlResultSummary::default(),
+            summary_base_ms: 0,
+            run_started_at: 0,
             cancellation_token: None,
         }
     }
@@ -74,21 +99,43 @@ impl CrawlerService {
             .unwrap_or_default();
 
         let start_time = chrono::Utc::now().timestamp_millis();
+        // 恢复的任务在已有摘要上继续累加
+        self.summary =
+            CrawlResultSummary::parse(task.result_summary.as_deref()).unwrap_or_default();
+        self.summary_base_ms = self.summary.duration_ms;
+        self.run_started_at = start_time;
         self.update_task_status(CrawlerTaskStatus::Running, None, Some(0.0), None)
             .await;
 
-        let base_url = "https://mikanani.me"; // TODO: Get from config
-        let fetcher = HttpFetcher::new();
-        let parser = MikanParser::new(base_url);
+        // 重新解析只读写数据库，不需要资源来源
+        if params.mode == CrawlerMode::Reprocess {
+            return self.run_reprocess(start_time).await;
+        }
+
+        let registry = SourceRegistry::from_config(&self.config);
+        let source_name = params.source.as_deref().unwrap_or(DEFAULT_SOURCE);
+        let parser = match registry.get(source_name) {
+            Ok(parser) => parser,
+            Err(e) => return Err(self.fail_task(e.to_string()).await),
+        };
+        let fetcher = HttpFetcher::new(&self.config.http());
         let limit = params.limit.map(|v| v as i64);
 
-        // 统一收集所有目标urls
-        let mut all_detail_urls = Vec::new();
-        let mut total_items = 0;
-        let mut error_message = None;
-        let mut failed = false;
+        // 已保存抓取边界的任务（重启或手动恢复）从未完成的详情页继续，跳过列表页
+        if !matches!(params.mode, CrawlerMode::Rss | CrawlerMode::Subscriptions) {
+            let (done, total) = repo.frontier_progress(self.task_id).await?;
+            if total > 0 {
+                let pending = repo.pending_frontier(self.task_id).await?;
+                tracing::info!("任务{}从断点恢复，已完成{}/{}", self.task_id, done, total);
+                self.total_items = total;
+                self.processed_items = done;
+                return self
+                    .run_details(pending, &fetcher, parser.as_ref(), start_time)
+                    .await;
+            }
+        }
 
-        match params.mode {
+        let list_urls: Vec<String> = match params.mode {
             CrawlerMode::Year => {
                 let year = params.year.unwrap_or_default();
                 let seasons = [
@@ -97,83 +144,110 @@ impl CrawlerService {
                     SeasonName::Autumn,
                     SeasonName::Winter,
                 ];
-                for season in seasons.iter() {
-                    let list_url = format!(
-                        "{}/Home/BangumiCoverFlowByDayOfWeek?year={}&seasonStr={}",
-                        base_url,
-                        year,
-                        season.as_str()
-                    );
-                    match fetcher.fetch(&list_url).await {
-                        Ok(html) => match parser.parse_list(&html) {
-                            Ok(mut urls) => {
-                                if let Some(lim) = limit {
-                                    urls.truncate(lim as usize);
-                                }
-                                total_items += urls.len();
-                                all_detail_urls.extend(urls);
-                            }
-                            Err(e) => {
-                                error_message = Some(format!("解析列表页失败: {}", e));
-                                failed = true;
-                                break;
-                            }
-                        },
-                        Err(e) => {
-                            error_message = Some(format!("fetch列表页失败: {}", e));
-                            failed = true;
-                            break;
-                        }
-                    }
-                }
+                seasons
+                    .iter()
+                    .filter_map(|season| parser.season_url(year, season))
+                    .collect()
             }
             CrawlerMode::Season => {
                 let year = params.year.unwrap_or_default();
-                let season = params.season.as_ref().map_or("春", |s| s.as_str());
-                let list_url = format!(
-                    "{}/Home/BangumiCoverFlowByDayOfWeek?year={}&seasonStr={}",
-                    base_url, year, season
-                );
-                match fetcher.fetch(&list_url).await {
-                    Ok(html) => match parser.parse_list(&html) {
-                        Ok(mut urls) => {
-                            if let Some(lim) = limit {
-                                urls.truncate(lim as usize);
-                            }
-                            total_items = urls.len();
-                            all_detail_urls = urls;
-                        }
-                        Err(e) => {
-                            error_message = Some(format!("解析列表页失败: {}", e));
-                            failed = true;
-                        }
-                    },
-                    Err(e) => {
-                        error_message = Some(format!("fetch列表页失败: {}", e));
-                        failed = true;
+                let season = params.season.clone().unwrap_or(SeasonName::Spring);
+                parser.season_url(year, &season).into_iter().collect()
+            }
+            CrawlerMode::Homepage => parser.homepage_url().into_iter().collect(),
+            CrawlerMode::Reprocess => unreachable!("重新解析任务已在获取来源前处理"),
+            CrawlerMode::Rss | CrawlerMode::Subscriptions => {
+                return self
+                    .run_rss(&params, &fetcher, parser.as_ref(), start_time)
+                    .await;
+            }
+            CrawlerMode::Bangumi => {
+                // 只抓取单部番剧的详情页
+                let detail_url = match self.target_mikan_id(&params).await? {
+                    Some(mikan_id) => parser.detail_url(mikan_id),
+                    None => {
+                        return Err(self
+                            .fail_task(
+                                "未找到要抓取的番剧，需提供有效的mikan_id或bangumi_id".to_string(),
+                            )
+                            .await);
                     }
-                }
+                };
+                let Some(detail_url) = detail_url else {
+                    return Err(self
+                        .fail_task(format!("资源来源{}不支持Bangumi模式", parser.name()))
+                        .await);
+                };
+                self.total_items = 1;
+                self.processed_items = 0;
+                repo.save_frontier(self.task_id, std::slice::from_ref(&detail_url))
+                    .await?;
+                return self
+                    .run_details(vec![detail_url], &fetcher, parser.as_ref(), start_time)
+                    .await;
             }
-            CrawlerMode::Homepage => {
-                let list_url = format!("{}/Home", base_url);
-                match fetcher.fetch(&list_url).await {
-                    Ok(html) => match parser.parse_list(&html) {
-                        Ok(mut urls) => {
-                            if let Some(lim) = limit {
-                                urls.truncate(lim as usize);
-                            }
-                            total_items = urls.len();
-                            all_detail_urls = urls;
+        };
+        if list_urls.is_empty() {
+            return Err(self
+                .fail_task(format!(
+                    "资源来源{}不支持{:?}模式",
+                    parser.name(),
+                    params.mode
+                ))
+                .await);
+        }
+
+        // 统一收集所有目标urls
+        let mut all_detail_urls = Vec::new();
+        let mut total_items = 0;
+        let mut error_message = None;
+        let mut failed = false;
+
+        for list_url in list_urls.iter() {
+            match fetcher.fetch(list_url).await {
+                Ok(html) => match parser.parse_list(&html) {
+                    Ok(mut urls) => {
+                        if let Some(issue) = parser.check_list(&urls) {
+                            tracing::warn!("列表页{}解析结果可疑: {}", list_url, issue.reason);
+                            self.quarantine(
+                                parser.as_ref(),
+                                QuarantinePageKind::List,
+                                list_url,
+                                None,
+                                html,
+                                issue.reason,
+                            )
+                            .await;
                         }
-                        Err(e) => {
-                            error_message = Some(format!("解析首页列表页失败: {}", e));
-                            failed = true;
+                        if let Some(lim) = limit {
+                            urls.truncate(lim as usize);
                         }
-                    },
+                        total_items += urls.len();
+                        all_detail_urls.extend(urls);
+                    }
                     Err(e) => {
-                        error_message = Some(format!("fetch首页列表页失败: {}", e));
+                        error_message = Some(format!("解析列表页失败: {}", e));
+                        self.quarantine(
+                            parser.as_ref(),
+                            QuarantinePageKind::List,
+                            list_url,
+                            None,
+                            html,
+                            format!("解析列表页失败: {}", e),
+                        )
+                        .await;
+                        self.summary
+                            .add_failed_page(list_url.clone(), format!("解析列表页失败: {}", e));
                         failed = true;
+                        break;
                     }
+                },
+                Err(e) => {
+                    error_message = Some(format!("fetch列表页失败: {}", e));
+                    self.summary
+                        .add_failed_page(list_url.clone(), format!("fetch列表页失败: {}", e));
+                    failed = true;
+                    break;
                 }
             }
         }
@@ -198,43 +272,60 @@ impl CrawlerService {
             )));
         }
 
+        // 保存抓取边界，任务中断后可从断点恢复
+        if let Err(e) = repo.save_frontier(self.task_id, &all_detail_urls).await {
+            return Err(self.fail_task(format!("保存抓取进度失败: {}", e)).await);
+        }
+
+        self.run_details(all_detail_urls, &fetcher, parser.as_ref(), start_time)
+            .await
+    }
+
+    /// 并发爬取详情页并分批入库
+    async fn run_details(
+        &mut self,
+        all_detail_urls: Vec<String>,
+        fetcher: &HttpFetcher,
+        parser: &dyn AnimeParser,
+        start_time: i64,
+    ) -> Result<()> {
         // 分批调度爬取详情，边爬边flush
         let max_concurrent = 8;
-        let mut processed = 0;
+        // 断点恢复时从已完成数继续计数
+        let mut processed = self.processed_items;
         let mut anime_data_buffer = Vec::new();
         let mut subtitle_group_buffer = Vec::new();
         let mut resource_buffer = Vec::new();
 
         let mut stream = stream::iter(all_detail_urls.into_iter().enumerate())
             .map(|(i, url)| {
-                let fetcher = &fetcher;
-                let parser = &parser;
-                let mikan_id = url
-                    .split('/')
-                    .last()
-                    .and_then(|s| s.parse::<i64>().ok())
-                    .unwrap_or(0);
+                let mikan_id = parser.detail_id(&url).unwrap_or(0);
                 async move {
-                    use tokio::time::{timeout, Duration};
-                    match timeout(Duration::from_secs(30), async {
-                        match fetcher.fetch(&url).await {
-                            Ok(html) => match parser.parse_detail(&html, mikan_id) {
-                                Ok(anime_data) => Ok(anime_data),
-                                Err(e) => Err(e),
-                            },
-                            Err(e) => Err(e.into()),
+                    // 超时与重试由 HttpFetcher 处理
+                    let result = match fetcher.fetch(&url).await {
+                        Ok(html) => parser.parse_detail(&html, mikan_id).map(|anime_data| {
+                            // 只有解析结果可疑时才保留原始页面
+                            let issue = parser.check_detail(&anime_data).map(|issue| (issue, html));
+                            (anime_data, issue)
+                        }),
+                        Err(e) => Err(e),
+                    };
+                    let result = match result {
+                        Ok((anime_data, Some(issue))) if issue.0.blocking => {
+                            Ok((anime_data, Some(issue)))
                         }
-                    })
-                    .await
-                    {
-                        Ok(Ok(anime_data)) => Some((i, anime_data)),
-                        Ok(Err(e)) => {
-                            tracing::warn!("爬取详情页{}失败: {}", url, e);
-                            None
+                        Ok((anime_data, issue)) => {
+                            expand_truncated_groups(fetcher, parser, mikan_id, anime_data)
+                                .await
+                                .map(|anime_data| (anime_data, issue))
                         }
-                        Err(_) => {
-                            tracing::warn!("爬取详情页{}超时", url);
-                            None
+                        Err(e) => Err(e),
+                    };
+                    match result {
+                        Ok((anime_data, issue)) => Ok((i, url, anime_data, issue)),
+                        Err(e) => {
+                            tracing::warn!("爬取详情页{}失败: {}", url, e);
+                            Err((url, e.to_string()))
                         }
                     }
                 }
@@ -260,7 +351,41 @@ impl CrawlerService {
                     return Err(AppError::Task(TaskError::Cancel("任务被取消".to_string())));
                 }
             }
-            if let Some((_, anime_data)) = result {
+            let page = match result {
+                Ok(page) => Some(page),
+                Err((url, reason)) => {
+                    self.summary.add_failed_page(url, reason);
+                    None
+                }
+            };
+            let page = match page {
+                Some((_, url, anime_data, Some((issue, html)))) => {
+                    tracing::warn!("详情页{}解析结果可疑: {}", url, issue.reason);
+                    self.quarantine(
+                        parser,
+                        QuarantinePageKind::Detail,
+                        &url,
+                        parser.detail_id(&url),
+                        html,
+                        issue.reason.clone(),
+                    )
+                    .await;
+                    if issue.blocking {
+                        // 已隔离的页面不再重试，修复解析器后通过重新解析入库
+                        self.summary
+                            .add_failed_page(url.clone(), format!("已隔离: {}", issue.reason));
+                        self.frontier_done.push(url);
+                        None
+                    } else {
+                        Some((url, anime_data))
+                    }
+                }
+                Some((_, url, anime_data, None)) => Some((url, anime_data)),
+                None => None,
+            };
+            if let Some((url, anime_data)) = page {
+                // 入库成功后该详情页才算完成，失败的页面在恢复时重试
+                self.frontier_done.push(url);
                 // 合并到本地缓冲区
                 if let Some(anime) = anime_data.anime {
                     if self.anime_ids.insert(anime.mikan_id) {
@@ -304,7 +429,7 @@ impl CrawlerService {
             processed += 1;
             self.processed_items = processed;
             // 每10条写入一次
-            if processed % 10 == 0 || processed as i64 == self.total_items {
+            if processed % 10 == 0 || processed == self.total_items {
                 // 将缓冲区数据写入self
                 self.anime_buffer.append(&mut anime_data_buffer);
                 self.subtitle_group_buffer
@@ -323,6 +448,298 @@ impl CrawlerService {
                 }
             }
         }
+        drop(stream);
+
+        self.anime_buffer.append(&mut anime_data_buffer);
+        self.subtitle_group_buffer
+            .append(&mut subtitle_group_buffer);
+        self.resource_buffer.append(&mut resource_buffer);
+        if let Err(e) = self.flush_buffers().await {
+            return Err(self.fail_task(format!("保存数据到数据库失败: {}", e)).await);
+        }
+
+        let elapsed = chrono::Utc::now().timestamp_millis() - start_time;
+        let speed = if elapsed > 0 {
+            self.processed_items as f64 / (elapsed as f64 / 1000.0)
+        } else {
+            0.0
+        };
+        self.update_task_status(CrawlerTaskStatus::Completed, None, Some(speed), Some(0.0))
+            .await;
+        Ok(())
+    }
+
+    /// 订阅源增量抓取：只处理尚未入库的资源条目。
+    /// Rss 模式读取任务参数指定的单个订阅源，Subscriptions 模式为每部已订阅番剧各查询一次
+    async fn run_rss(
+        &mut self,
+        params: &CrawlerTaskCreate,
+        fetcher: &HttpFetcher,
+        parser: &dyn AnimeParser,
+        start_time: i64,
+    ) -> Result<()> {
+        let feed_urls: Vec<String> = if params.mode == CrawlerMode::Subscriptions {
+            let animes = AnimeRepository::new(&self.pool).list_subscribed().await?;
+            let mut urls = Vec::new();
+            for anime in animes {
+                let anime_params = CrawlerTaskCreate {
+                    mode: CrawlerMode::Rss,
+                    source: params.source.clone(),
+                    mikan_id: Some(anime.mikan_id),
+                    keyword: self.feed_keyword(parser, anime.mikan_id).await?,
+                    ..Default::default()
+                };
+                urls.extend(parser.feed_url(&anime_params));
+            }
+            urls
+        } else {
+            let mut params = params.clone();
+            if params.keyword.is_none() {
+                if let Some(mikan_id) = params.mikan_id {
+                    params.keyword = self.feed_keyword(parser, mikan_id).await?;
+                }
+            }
+            match parser.feed_url(&params) {
+                Some(url) => vec![url],
+                None => {
+                    return Err(self
+                        .fail_task(format!("RSS任务参数不完整或来源{}不支持RSS", parser.name()))
+                        .await);
+                }
+            }
+        };
+
+        let mut items: Vec<FeedItem> = Vec::new();
+        let mut seen_hashes = HashSet::new();
+        let mut failed_feeds = 0;
+        // 订阅源的 ETag/Last-Modified，入库成功后再保存，未变化的订阅源直接跳过
+        let mut pending_validators = Vec::new();
+        for feed_url in &feed_urls {
+            if self.is_cancelled() {
+                return Err(self.cancel_running_task(start_time).await);
+            }
+            let result = match fetcher.fetch_conditional(feed_url).await {
+                Ok(FetchOutcome::NotModified) => {
+                    tracing::debug!("订阅源未变化，跳过");
+                    Ok(Vec::new())
+                }
+                Ok(FetchOutcome::Fetched { body, validators }) => {
+                    if let Some(validators) = validators {
+                        pending_validators.push((feed_url.clone(), validators));
+                    }
+                    parser
+                        .parse_feed(&body)
+                        .map_err(|e| format!("解析RSS失败: {}", e))
+                }
+                Err(e) => Err(format!("fetch RSS失败: {}", e)),
+            };
+            match result {
+                Ok(feed_items) => items.extend(feed_items.into_iter().filter(|item| {
+                    // 同一资源可能出现在多个番剧的搜索结果中
+                    item.magnet_hash
+                        .as_ref()
+                        .is_none_or(|hash| seen_hashes.insert(hash.clone()))
+                })),
+                Err(e) if feed_urls.len() == 1 => {
+                    self.summary
+                        .add_failed_page(redact_url(feed_url), e.clone());
+                    return Err(self.fail_task(e).await);
+                }
+                Err(e) => {
+                    tracing::warn!("来源{}的订阅源抓取失败: {}", parser.name(), e);
+                    self.summary.add_failed_page(redact_url(feed_url), e);
+                    failed_feeds += 1;
+                }
+            }
+        }
+        if !feed_urls.is_empty() && failed_feeds == feed_urls.len() {
+            return Err(self.fail_task("所有订阅源均抓取失败".to_string()).await);
+        }
+        // 过滤已入库的资源，只处理新条目
+        let resource_repo = ResourceRepository::new(&self.pool);
+        let hashes: Vec<String> = items.iter().filter_map(|i| i.magnet_hash.clone()).collect();
+        let existing = resource_repo.existing_hashes(&hashes).await?;
+
+        // 已入库资源只刷新做种/下载人数
+        let health: Vec<(String, Option<i64>, Option<i64>)> = items
+            .iter()
+            .filter(|i| i.seeders.is_some() || i.leechers.is_some())
+            .filter_map(|i| {
+                let hash = i.magnet_hash.as_ref().filter(|h| existing.contains(*h))?;
+                Some((hash.clone(), i.seeders, i.leechers))
+            })
+            .collect();
+        resource_repo.update_health(&health).await?;
+        let new_items: Vec<FeedItem> = items
+            .into_iter()
+            .filter(|i| {
+                i.magnet_hash
+                    .as_ref()
+                    .is_some_and(|hash| !existing.contains(hash))
+            })
+            .collect();
+        self.total_items = new_items.len() as i64;
+        self.processed_items = 0;
+        // URL 中可能带有 API key，日志只记录订阅源数量
+        tracing::info!(
+            "来源{} 订阅源数: {}，新资源数: {}",
+            parser.name(),
+            feed_urls.len(),
+            self.total_items
+        );
+        self.update_task_status(CrawlerTaskStatus::Running, None, Some(0.0), None)
+            .await;
+
+        // 番剧RSS且指定了字幕组时，所有条目的归属已知；否则需要从资源页解析
+        let known_target = match (&params.rss_token, params.mikan_id, params.subgroup_id) {
+            (None, Some(mikan_id), Some(group_id))
+                if parser.feed_mapping() == FeedMapping::EpisodePage =>
+            {
+                Some((mikan_id, group_id))
+            }
+            _ => None,
+        };
+
+        let resolved = match parser.feed_mapping() {
+            FeedMapping::EpisodePage => {
+                self.resolve_by_episode_page(new_items, fetcher, parser, known_target, start_time)
+                    .await?
+            }
+            FeedMapping::TitleMatch => self.resolve_by_title(new_items, parser).await?,
+        };
+
+        // 补全数据库中缺失的番剧与字幕组，避免外键约束失败
+        let anime_repo = AnimeRepository::new(&self.pool);
+        let group_repo = SubtitleGroupRepository::new(&self.pool);
+        let mikan_ids: HashSet<i64> = resolved.iter().map(|r| r.mikan_id).collect();
+        for mikan_id in mikan_ids {
+            let group_missing = match known_target {
+                Some((_, group_id)) => group_repo.get_by_id(group_id).await?.is_none(),
+                None => false,
+            };
+            if anime_repo.get_by_id(mikan_id).await?.is_some() && !group_missing {
+                continue;
+            }
+            let Some(detail_url) = parser.detail_url(mikan_id) else {
+                continue;
+            };
+            let anime_data = match fetcher.fetch(&detail_url).await {
+                Ok(html) => parser.parse_detail(&html, mikan_id),
+                Err(e) => Err(e),
+            };
+            match anime_data {
+                Ok(anime_data) => {
+                    if let Some(anime) = anime_data.anime {
+                        if self.anime_ids.insert(anime.mikan_id) {
+                            self.anime_buffer.push(anime);
+                        }
+                    }
+                    for group in anime_data.subtitle_groups {
+                        if let Some(id) = group.id {
+                            if self.subtitle_group_ids.insert(id) {
+                                self.subtitle_group_buffer.push(group);
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("爬取详情页{}失败: {}", detail_url, e);
+                    self.summary.add_failed_page(detail_url, e.to_string());
+                }
+            }
+        }
+
+        // 丢弃仍无法关联到番剧或字幕组的资源
+        let mut known_groups = HashSet::new();
+        for resource in resolved {
+            let anime_known = self.anime_ids.contains(&resource.mikan_id)
+                || anime_repo.get_by_id(resource.mikan_id).await?.is_some();
+            let group_known = self
+                .subtitle_group_ids
+                .contains(&resource.subtitle_group_id)
+                || known_groups.contains(&resource.subtitle_group_id)
+                || group_repo
+                    .get_by_id(resource.subtitle_group_id)
+                    .await?
+                    .is_some();
+            if !anime_known || !group_known {
+                tracing::warn!("资源{}无法关联番剧或字幕组，已跳过", resource.title);
+                continue;
+            }
+            known_groups.insert(resource.subtitle_group_id);
+            if let Some(hash) = &resource.magnet_hash {
+                if self.resource_hashes.insert(hash.clone()) {
+                    self.resource_buffer.push(resource);
+                }
+            }
+        }
+
+        if let Err(e) = self.flush_buffers().await {
+            return Err(self.fail_task(format!("保存数据到数据库失败: {}", e)).await);
+        }
+        for (url, validators) in &pending_validators {
+            fetcher.save_validators(url, validators);
+        }
+
+        let elapsed = chrono::Utc::now().timestamp_millis() - start_time;
+        let speed = if elapsed > 0 {
+            self.processed_items as f64 / (elapsed as f64 / 1000.0)
+        } else {
+            0.0
+        };
+        self.update_task_status(CrawlerTaskStatus::Completed, None, Some(speed), Some(0.0))
+            .await;
+        Ok(())
+    }
+
+    /// 重新解析解析器版本早于当前版本的资源，中断后再次运行时只处理剩余资源
+    async fn run_reprocess(&mut self, start_time: i64) -> Result<()> {
+        let service = ParserService::new(self.pool.clone());
+        let remaining = match service.count_outdated().await {
+            Ok(count) => count,
+            Err(e) => return Err(self.fail_task(format!("统计待解析资源失败: {}", e)).await),
+        };
+        self.processed_items = 0;
+        self.total_items = remaining;
+        let mut after_id = 0;
+        loop {
+            if self.is_cancelled() {
+                return Err(self.cancel_running_task(start_time).await);
+            }
+            let batch = match service.reprocess_batch(after_id).await {
+                Ok(Some(batch)) => batch,
+                Ok(None) => break,
+                Err(e) => return Err(self.fail_task(format!("重新解析资源失败: {}", e)).await),
+            };
+            after_id = batch.last_id;
+            self.processed_items += batch.processed;
+            self.summary.resources_reparsed += batch.changed;
+
+            let elapsed = chrono::Utc::now().timestamp_millis() - start_time;
+            let speed = if elapsed > 0 {
+                self.processed_items as f64 / (elapsed as f64 / 1000.0)
+            } else {
+                0.0
+            };
+            let remaining = if speed > 0.0 {
+                (self.total_items - self.processed_items).max(0) as f64 / speed
+            } else {
+                0.0
+            };
+            self.update_task_status(
+                CrawlerTaskStatus::Running,
+                None,
+                Some(speed),
+                Some(remaining),
+            )
+            .await;
+        }
+        tracing::info!(
+            "任务{}重新解析{}条资源，其中{}条有变化",
+            self.task_id,
+            self.processed_items,
+            self.summary.resources_reparsed
+        );
 
         let elapsed = chrono::Utc::now().timestamp_millis() - start_time;
         let speed = if elapsed > 0 {
@@ -335,7 +752,251 @@ impl CrawlerService {
         Ok(())
     }
 
+    /// Bangumi 模式的目标番剧：优先使用mikan_id，否则按bangumi_id查找已入库番剧
+    async fn target_mikan_id(&self, params: &CrawlerTaskCreate) -> Result<Option<i64>> {
+        if let Some(mikan_id) = params.mikan_id {
+            return Ok(Some(mikan_id));
+        }
+        let Some(bangumi_id) = params.bangumi_id else {
+            return Ok(None);
+        };
+        Ok(AnimeRepository::new(&self.pool)
+            .mikan_ids_by_bangumi_id(bangumi_id)
+            .await?
+            .into_iter()
+            .next())
+    }
+
+    /// 按标题匹配的来源按番剧搜索时使用的关键词：番剧标题，来源偏好时优先使用别名
+    async fn feed_keyword(
+        &self,
+        parser: &dyn AnimeParser,
+        mikan_id: i64,
+    ) -> Result<Option<String>> {
+        if parser.feed_mapping() != FeedMapping::TitleMatch {
+            return Ok(None);
+        }
+        if parser.prefers_alias_keyword() {
+            let alias = AnimeAliasRepository::new(&self.pool)
+                .list_by_mikan_id(mikan_id)
+                .await?
+                .into_iter()
+                .next();
+            if let Some(alias) = alias {
+                return Ok(Some(alias.alias));
+            }
+        }
+        Ok(AnimeRepository::new(&self.pool)
+            .get_by_id(mikan_id)
+            .await?
+            .map(|anime| anime.title))
+    }
+
+    /// 抓取条目的资源页，解析所属番剧与字幕组
+    async fn resolve_by_episode_page(
+        &mut self,
+        items: Vec<FeedItem>,
+        fetcher: &HttpFetcher,
+        parser: &dyn AnimeParser,
+        known_target: Option<(i64, i64)>,
+        start_time: i64,
+    ) -> Result<Vec<Resource>> {
+        // 已知番剧与字幕组时无需抓取资源页
+        if let Some((mikan_id, group_id)) = known_target {
+            self.processed_items += items.len() as i64;
+            return Ok(items
+                .into_iter()
+                .map(|item| item.into_resource(parser.name(), mikan_id, group_id))
+                .collect());
+        }
+
+        // 缺少资源页链接的条目无法确定番剧与字幕组，按条目记录失败
+        let mut pending = Vec::with_capacity(items.len());
+        for item in items {
+            match item.page_url.clone() {
+                Some(page_url) => pending.push((item, page_url)),
+                None => {
+                    self.summary
+                        .add_failed_item(item.title, "条目缺少资源页链接".to_string());
+                    self.processed_items += 1;
+                }
+            }
+        }
+
+        let max_concurrent = 8;
+        let mut stream = stream::iter(pending)
+            .map(|(item, page_url)| async move {
+                match fetcher.fetch(&page_url).await {
+                    Ok(html) => match parser.parse_episode_page(&html) {
+                        Some((mikan_id, group)) => Ok((item, mikan_id, group)),
+                        None => {
+                            tracing::warn!("解析资源页{}失败：未找到番剧或字幕组", page_url);
+                            Err((page_url, "未找到番剧或字幕组".to_string()))
+                        }
+                    },
+                    Err(e) => {
+                        tracing::warn!("爬取资源页{}失败: {}", page_url, e);
+                        Err((page_url, e.to_string()))
+                    }
+                }
+            })
+            .buffer_unordered(max_concurrent);
+
+        let mut resolved = Vec::new();
+        while let Some(result) = stream.next().await {
+            if self.is_cancelled() {
+                return Err(self.cancel_running_task(start_time).await);
+            }
+            match result {
+                Ok((item, mikan_id, group)) => {
+                    let group_id = group.id.unwrap_or_default();
+                    if self.subtitle_group_ids.insert(group_id) {
+                        self.subtitle_group_buffer.push(group);
+                    }
+                    resolved.push(item.into_resource(parser.name(), mikan_id, group_id));
+                }
+                Err((url, reason)) => self.summary.add_failed_page(url, reason),
+            }
+            self.processed_items += 1;
+        }
+        Ok(resolved)
+    }
+
+    /// 按标题匹配已入库番剧，按发布组名称获取或新建字幕组
+    async fn resolve_by_title(
+        &mut self,
+        items: Vec<FeedItem>,
+        parser: &dyn AnimeParser,
+    ) -> Result<Vec<Resource>> {
+        let animes = AnimeRepository::new(&self.pool).list(0, 0).await?;
+        let mut matcher = TitleMatcher::new(&animes);
+        for alias in AnimeAliasRepository::new(&self.pool).list(0, 0).await? {
+            matcher.add_title(&alias.alias, alias.mikan_id);
+        }
+        let group_repo = SubtitleGroupRepository::new(&self.pool);
+        let mut group_ids: HashMap<String, i64> = HashMap::new();
+        let mut resolved = Vec::new();
+        for item in items {
+            self.processed_items += 1;
+            let Some(mikan_id) = matcher.match_title(&item.title) else {
+                tracing::debug!("资源{}未匹配到已入库番剧，已跳过", item.title);
+                continue;
+            };
+            let group_name = item
+                .group_name
+                .clone()
+                .unwrap_or_else(|| UNKNOWN_GROUP_NAME.to_string());
+            let group_id = match group_ids.get(&group_name) {
+                Some(id) => *id,
+                None => {
+                    let (group, created) = group_repo.get_or_create_by_name(&group_name).await?;
+                    let id = group.id.unwrap_or_default();
+                    if created {
+                        self.summary.new_subtitle_groups.push(SummaryGroup {
+                            id,
+                            name: group.name,
+                        });
+                    }
+                    group_ids.insert(group_name, id);
+                    id
+                }
+            };
+            resolved.push(item.into_resource(parser.name(), mikan_id, group_id));
+        }
+        Ok(resolved)
+    }
+
+    /// 保存解析结果可疑的原始页面，失败时只记录日志
+    async fn quarantine(
+        &self,
+        parser: &dyn AnimeParser,
+        page_kind: QuarantinePageKind,
+        url: &str,
+        mikan_id: Option<i64>,
+        raw_html: String,
+        reason: String,
+    ) {
+        let page = ParseQuarantine {
+            id: None,
+            task_id: Some(self.task_id),
+            source: parser.name().to_string(),
+            page_kind,
+            url: url.to_string(),
+            mikan_id,
+            reason,
+            raw_html,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = ParseQuarantineRepository::new(&self.pool)
+            .create(&page)
+            .await
+        {
+            tracing::error!("保存隔离页面{}失败: {}", url, e);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation_token
+            .as_ref()
+            .is_some_and(|token| token.is_cancelled())
+    }
+
+    /// 将任务标记为取消，返回对应错误
+    async fn cancel_running_task(&self, start_time: i64) -> AppError {
+        let elapsed = chrono::Utc::now().timestamp_millis() - start_time;
+        let speed = if elapsed > 0 {
+            self.processed_items as f64 / (elapsed as f64 / 1000.0)
+        } else {
+            0.0
+        };
+        self.update_task_status(
+            CrawlerTaskStatus::Cancelled,
+            Some("任务被取消".to_string()),
+            Some(speed),
+            None,
+        )
+        .await;
+        AppError::Task(TaskError::Cancel("任务被取消".to_string()))
+    }
+
+    /// 将任务标记为失败，返回对应错误
+    async fn fail_task(&self, error_msg: String) -> AppError {
+        self.update_task_status(
+            CrawlerTaskStatus::Failed,
+            Some(error_msg.clone()),
+            Some(0.0),
+            None,
+        )
+        .await;
+        AppError::Task(TaskError::Failed(error_msg))
+    }
+
     async fn flush_buffers(&mut self) -> crate::error::Result<()> {
+        // 写入前记录已存在的数据，用于区分结果摘要中的新增与更新
+        let anime_ids: Vec<i64> = self.anime_buffer.iter().map(|a| a.mikan_id).collect();
+        let existing_animes = AnimeRepository::new(&self.pool)
+            .existing_ids(&anime_ids)
+            .await?;
+        let group_ids: Vec<i64> = self
+            .subtitle_group_buffer
+            .iter()
+            .filter_map(|g| g.id)
+            .collect();
+        let existing_groups: HashSet<i64> = SubtitleGroupRepository::new(&self.pool)
+            .get_by_ids(&group_ids)
+            .await?
+            .into_iter()
+            .filter_map(|g| g.id)
+            .collect();
+        let hashes: Vec<String> = self
+            .resource_buffer
+            .iter()
+            .filter_map(|r| r.magnet_hash.clone())
+            .collect();
+        let existing_hashes = ResourceRepository::new(&self.pool)
+            .existing_hashes(&hashes)
+            .await?;
+
         let mut tx = self.pool.begin().await?;
 
         let anime_repo = AnimeRepository::new(&self.pool);
@@ -353,7 +1014,51 @@ impl CrawlerService {
             .insert_many_resources(&mut tx, &self.resource_buffer)
             .await?;
 
+        CrawlerTaskRepository::new(&self.pool)
+            .mark_frontier_done(&mut tx, self.task_id, &self.frontier_done)
+            .await?;
+
         tx.commit().await?;
+        self.frontier_done.clear();
+
+        for anime in &self.anime_buffer {
+            let summary = &mut self.summary;
+            let recorded = summary
+                .animes_added
+                .iter()
+                .chain(summary.animes_updated.iter())
+                .any(|a| a.mikan_id == anime.mikan_id);
+            if recorded {
+                continue;
+            }
+            let entry = SummaryAnime {
+                mikan_id: anime.mikan_id,
+                title: anime.title.clone(),
+            };
+            if existing_animes.contains(&anime.mikan_id) {
+                summary.animes_updated.push(entry);
+            } else {
+                summary.animes_added.push(entry);
+            }
+        }
+        for group in &self.subtitle_group_buffer {
+            if let Some(id) = group.id.filter(|id| !existing_groups.contains(id)) {
+                self.summary.new_subtitle_groups.push(SummaryGroup {
+                    id,
+                    name: group.name.clone(),
+                });
+            }
+        }
+        for resource in &self.resource_buffer {
+            if resource
+                .magnet_hash
+                .as_ref()
+                .is_some_and(|hash| !existing_hashes.contains(hash))
+            {
+                self.summary
+                    .add_resource(resource.mikan_id, resource.episode_number);
+            }
+        }
 
         self.anime_buffer.clear();
         self.subtitle_group_buffer.clear();
@@ -383,6 +1088,10 @@ impl CrawlerService {
             task.total_items = Some(self.total_items);
             task.error_message = error_message;
             task.processing_speed = processing_speed;
+            let mut summary = self.summary.clone();
+            summary.duration_ms =
+                self.summary_base_ms + chrono::Utc::now().timestamp_millis() - self.run_started_at;
+            task.result_summary = serde_json::to_string(&summary).ok();
             task.estimated_remaining = estimated_remaining;
 
             if task.status == CrawlerTaskStatus::Completed
@@ -401,6 +1110,7 @@ impl CrawlerService {
     /// 创建爬虫任务并唤醒worker
     pub async fn create_task(
         pool: Arc<SqlitePool>,
+        config: &Config,
         notify: Arc<tokio::sync::Notify>,
         task: crate::types::crawler::CrawlerTaskCreate,
     ) -> Result<crate::types::crawler::TaskResponse> {
@@ -408,6 +1118,15 @@ impl CrawlerService {
         use crate::models::{CrawlerTask, CrawlerTaskStatus, CrawlerTaskType};
         use crate::repositories::crawler_task::CrawlerTaskRepository;
         use crate::types::crawler::TaskResponse;
+        // 提前校验来源，避免任务进入队列后才失败
+        SourceRegistry::from_config(config)
+            .get(task.source.as_deref().unwrap_or(DEFAULT_SOURCE))?;
+        if task.mode == CrawlerMode::Bangumi && task.mikan_id.is_none() && task.bangumi_id.is_none()
+        {
+            return Err(AppError::Input(crate::error::InputError::Invalid(
+                "Bangumi模式需要提供mikan_id或bangumi_id".to_string(),
+            )));
+        }
         let repo = CrawlerTaskRepository::new(&pool);
         let parameters = serde_json::to_string(&task).unwrap_or_default();
         let current_time = chrono::Utc::now().timestamp_millis();
@@ -442,7 +1161,7 @@ impl CrawlerService {
                 task_type: task.task_type.into(),
                 status: task.status.into(),
                 parameters: task.parameters,
-                result_summary: task.result_summary,
+                result_summary: CrawlResultSummary::parse(task.result_summary.as_deref()),
                 created_at: Some(task.created_at.unwrap_or_default()),
                 started_at: task.started_at,
                 completed_at: task.completed_at,
@@ -459,6 +1178,103 @@ impl CrawlerService {
         }
     }
 
+    /// 将失败或已取消的任务重新放回队列，worker从抓取边界继续执行
+    pub async fn resume_task(
+        pool: Arc<SqlitePool>,
+        notify: Arc<tokio::sync::Notify>,
+        task_id: i64,
+    ) -> Result<crate::models::CrawlerTask> {
+        let repo = CrawlerTaskRepository::new(&pool);
+        let mut task = repo
+            .get_by_id(task_id)
+            .await?
+            .ok_or_else(|| AppError::Task(TaskError::Failed("任务不存在".to_string())))?;
+        if !matches!(
+            task.status,
+            CrawlerTaskStatus::Failed | CrawlerTaskStatus::Cancelled
+        ) {
+            return Err(AppError::Task(TaskError::Failed(
+                "只能恢复失败或已取消的任务".to_string(),
+            )));
+        }
+        task.status = CrawlerTaskStatus::Pending;
+        task.completed_at = None;
+        task.error_message = None;
+        repo.update(&task).await?;
+        notify.notify_one();
+        Ok(task)
+    }
+
+    /// 用当前解析器重新解析隔离页面。解析结果可信时写入数据库并删除隔离记录，
+    /// 列表页只检查能否解析出详情页链接，需重新创建任务抓取
+    pub async fn reparse_quarantined(
+        pool: Arc<SqlitePool>,
+        config: &Config,
+        id: i64,
+    ) -> Result<QuarantineReparseResult> {
+        let repo = ParseQuarantineRepository::new(&pool);
+        let page = repo.get_by_id(id).await?.ok_or_else(|| {
+            AppError::Domain(crate::error::DomainError::NotFound {
+                resource_type: "parse_quarantine".to_string(),
+                resource_id: id,
+            })
+        })?;
+        let registry = SourceRegistry::from_config(config);
+        let parser = registry.get(&page.source)?;
+
+        let mut result = QuarantineReparseResult {
+            id,
+            resolved: false,
+            ingested: false,
+            reason: None,
+            resource_count: 0,
+        };
+        let issue = match page.page_kind {
+            QuarantinePageKind::List => {
+                let urls = parser.parse_list(&page.raw_html)?;
+                parser.check_list(&urls)
+            }
+            QuarantinePageKind::Detail => {
+                let mikan_id = page
+                    .mikan_id
+                    .or_else(|| parser.detail_id(&page.url))
+                    .unwrap_or(0);
+                let anime_data = parser.parse_detail(&page.raw_html, mikan_id)?;
+                let issue = parser.check_detail(&anime_data);
+                if issue.as_ref().is_none_or(|issue| !issue.blocking) {
+                    result.resource_count = anime_data.resources.len() as i64;
+                    let mut tx = pool.begin().await?;
+                    AnimeRepository::new(&pool)
+                        .insert_many_animes(
+                            &mut tx,
+                            &anime_data.anime.into_iter().collect::<Vec<_>>(),
+                        )
+                        .await?;
+                    SubtitleGroupRepository::new(&pool)
+                        .insert_many_subtitle_groups(&mut tx, &anime_data.subtitle_groups)
+                        .await?;
+                    ResourceRepository::new(&pool)
+                        .insert_many_resources(&mut tx, &anime_data.resources)
+                        .await?;
+                    tx.commit().await?;
+                    result.ingested = true;
+                }
+                issue
+            }
+        };
+        match issue {
+            Some(issue) => {
+                repo.update_reason(id, &issue.reason).await?;
+                result.reason = Some(issue.reason);
+            }
+            None => {
+                repo.delete(id).await?;
+                result.resolved = true;
+            }
+        }
+        Ok(result)
+    }
+
     /// 取消爬虫任务并更新状态
     pub async fn cancel_task(
         pool: Arc<SqlitePool>,
@@ -492,7 +1308,7 @@ impl CrawlerService {
                         task_type: task.task_type.into(),
                         status: task.status.into(),
                         parameters: task.parameters,
-                        result_summary: task.result_summary,
+                        result_summary: CrawlResultSummary::parse(task.result_summary.as_deref()),
                         created_at: Some(task.created_at.unwrap_or_default()),
                         started_at: task.started_at,
                         completed_at: task.completed_at,
@@ -512,3 +1328,34 @@ impl CrawlerService {
         }
     }
 }
+
+/// 补全详情页中被截断的字幕组资源表。
+/// 任一字幕组展开失败时整页视为失败，恢复任务时重试，避免旧剧集资源静默缺失
+async fn expand_truncated_groups(
+    fetcher: &HttpFetcher,
+    parser: &dyn AnimeParser,
+    mikan_id: i64,
+    mut anime_data: AnimeData,
+) -> Result<AnimeData> {
+    for group_id in std::mem::take(&mut anime_data.truncated_groups) {
+        let Some(expand_url) = parser.expand_url(mikan_id, group_id) else {
+            continue;
+        };
+        let html = fetcher.fetch(&expand_url).await?;
+        let resources = parser.parse_expanded_table(&html, mikan_id, group_id)?;
+        // 展开表包含已渲染的行，按magnet_hash去重
+        let known: HashSet<String> = anime_data
+            .resources
+            .iter()
+            .filter_map(|r| r.magnet_hash.clone())
+            .collect();
+        anime_data
+            .resources
+            .extend(resources.into_iter().filter(|r| {
+                r.magnet_hash
+                    .as_ref()
+                    .is_none_or(|hash| !known.contains(hash))
+            }));
+    }
+    Ok(anime_data)
+}
@@ -37,6 +37,7 @@ impl DownloadService {
             // 性能优化配置
             paused: false,    // 立即开始下载
             overwrite: false, // 不覆盖已存在的文件
+            only_files: task.only_files.clone().filter(|files| !files.is_empty()),
             ..Default::default()
         };
         let resp = self
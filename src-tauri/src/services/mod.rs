@@ -1,4 +1,6 @@
 pub mod bangumi_service;
 pub mod crawler_service;
 pub mod download_service;
+pub mod parser_service;
 pub mod subscription_service;
+pub mod torrent_service;
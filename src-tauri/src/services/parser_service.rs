@@ -0,0 +1,219 @@
+use crate::core::text_parser;
+use crate::error::Result;
+use crate::models::Resource;
+use crate::repositories::resource::ResourceRepository;
+use crate::types::crawler::{DisagreementSample, FieldDisagreement, ParserEvaluation};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+// 每批读取的资源数
+const BATCH_SIZE: i64 = 1000;
+// 重新解析任务每批更新的资源数
+const REPROCESS_BATCH_SIZE: i64 = 500;
+
+/// 一批重新解析的结果
+pub struct ReprocessBatch {
+    // 本批最后一条资源的ID，下一批从其后继续
+    pub last_id: i64,
+    pub processed: i64,
+    // 字段有变化的资源数
+    pub changed: i64,
+}
+
+/// 用当前解析器重新解析已入库的资源标题
+pub struct ParserService {
+    pool: Arc<SqlitePool>,
+}
+
+impl ParserService {
+    pub fn new(pool: Arc<SqlitePool>) -> Self {
+        Self { pool }
+    }
+
+    /// 解析器版本早于当前版本的资源数
+    pub async fn count_outdated(&self) -> Result<i64> {
+        ResourceRepository::new(&self.pool)
+            .count_outdated(text_parser::PARSER_VERSION)
+            .await
+    }
+
+    /// 重新解析 after_id 之后的一批旧版本资源并保存，没有剩余资源时返回None
+    pub async fn reprocess_batch(&self, after_id: i64) -> Result<Option<ReprocessBatch>> {
+        let repo = ResourceRepository::new(&self.pool);
+        let mut resources = repo
+            .list_outdated(after_id, text_parser::PARSER_VERSION, REPROCESS_BATCH_SIZE)
+            .await?;
+        let Some(last_id) = resources.last().and_then(|r| r.id) else {
+            return Ok(None);
+        };
+        let mut changed = 0;
+        for resource in &mut resources {
+            // 只统计解析字段的变化，解析器版本总会更新
+            let mut before = resource.clone();
+            before.parser_version = Some(text_parser::PARSER_VERSION);
+            reparse(resource);
+            if *resource != before {
+                changed += 1;
+            }
+        }
+        repo.save_reparsed(&resources).await?;
+        Ok(Some(ReprocessBatch {
+            last_id,
+            processed: resources.len() as i64,
+            changed,
+        }))
+    }
+
+    /// 对比已存储字段与重新解析的结果，统计各字段的不一致率，每个字段最多保留 sample_limit 条样例
+    pub async fn evaluate(&self, sample_limit: usize) -> Result<ParserEvaluation> {
+        let repo = ResourceRepository::new(&self.pool);
+        let mut fields: Vec<FieldDisagreement> = Vec::new();
+        let mut total = 0;
+        let mut after_id = 0;
+        loop {
+            let batch = repo.list_after(after_id, BATCH_SIZE).await?;
+            let Some(last) = batch.last() else {
+                break;
+            };
+            after_id = last.id.unwrap_or_default();
+            for resource in &batch {
+                total += 1;
+                let mut reparsed = resource.clone();
+                reparse(&mut reparsed);
+                for (field, stored, reparsed) in compare_fields(resource, &reparsed) {
+                    let index = match fields.iter().position(|f| f.field == field) {
+                        Some(index) => index,
+                        None => {
+                            fields.push(FieldDisagreement {
+                                field: field.to_string(),
+                                disagreements: 0,
+                                rate: 0.0,
+                                samples: Vec::new(),
+                            });
+                            fields.len() - 1
+                        }
+                    };
+                    if stored == reparsed {
+                        continue;
+                    }
+                    let entry = &mut fields[index];
+                    entry.disagreements += 1;
+                    if entry.samples.len() < sample_limit {
+                        entry.samples.push(DisagreementSample {
+                            resource_id: resource.id.unwrap_or_default(),
+                            title: resource.title.clone(),
+                            stored,
+                            parsed: reparsed,
+                        });
+                    }
+                }
+            }
+        }
+        for entry in &mut fields {
+            entry.rate = entry.disagreements as f64 / total as f64;
+        }
+        tracing::info!("解析器评估：共{}条资源", total);
+        Ok(ParserEvaluation { total, fields })
+    }
+}
+
+/// 按当前解析器重新解析资源标题，标题中未识别出字幕类型时保留原值（可能来自订阅源）
+fn reparse(resource: &mut Resource) {
+    let fallback = resource.subtitle_type.take();
+    text_parser::parse_release(&resource.title).apply_to(resource, fallback);
+}
+
+fn text<T: ToString>(value: Option<T>) -> Option<String> {
+    value.map(|v| v.to_string())
+}
+
+fn debug<T: std::fmt::Debug>(value: Option<T>) -> Option<String> {
+    value.map(|v| format!("{:?}", v))
+}
+
+/// 逐字段列出已存储的值与重新解析后的值
+fn compare_fields(
+    stored: &Resource,
+    reparsed: &Resource,
+) -> Vec<(&'static str, Option<String>, Option<String>)> {
+    vec![
+        (
+            "episode_number",
+            text(stored.episode_number),
+            text(reparsed.episode_number),
+        ),
+        (
+            "episode_end",
+            text(stored.episode_end),
+            text(reparsed.episode_end),
+        ),
+        ("season", text(stored.season), text(reparsed.season)),
+        (
+            "episode_kind",
+            debug(Some(stored.episode_kind)),
+            debug(Some(reparsed.episode_kind)),
+        ),
+        (
+            "episode_sort",
+            text(stored.episode_sort),
+            text(reparsed.episode_sort),
+        ),
+        ("version", text(stored.version), text(reparsed.version)),
+        (
+            "resolution",
+            stored.resolution.clone(),
+            reparsed.resolution.clone(),
+        ),
+        (
+            "subtitle_type",
+            stored.subtitle_type.clone(),
+            reparsed.subtitle_type.clone(),
+        ),
+        (
+            "subtitle_languages",
+            stored.subtitle_languages.clone(),
+            reparsed.subtitle_languages.clone(),
+        ),
+        (
+            "subtitle_delivery",
+            debug(stored.subtitle_delivery),
+            debug(reparsed.subtitle_delivery),
+        ),
+        (
+            "audio_language",
+            stored.audio_language.clone(),
+            reparsed.audio_language.clone(),
+        ),
+        (
+            "video_codec",
+            stored.video_codec.clone(),
+            reparsed.video_codec.clone(),
+        ),
+        (
+            "bit_depth",
+            text(stored.bit_depth),
+            text(reparsed.bit_depth),
+        ),
+        ("hdr", stored.hdr.clone(), reparsed.hdr.clone()),
+        (
+            "audio_codec",
+            stored.audio_codec.clone(),
+            reparsed.audio_codec.clone(),
+        ),
+        (
+            "release_source",
+            stored.release_source.clone(),
+            reparsed.release_source.clone(),
+        ),
+        (
+            "platform",
+            stored.platform.clone(),
+            reparsed.platform.clone(),
+        ),
+        (
+            "container",
+            stored.container.clone(),
+            reparsed.container.clone(),
+        ),
+    ]
+}
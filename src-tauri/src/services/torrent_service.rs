@@ -0,0 +1,105 @@
+use crate::config::Config;
+use crate::core::http_fetcher::HttpFetcher;
+use crate::core::torrent_parser;
+use crate::error::{AppError, DomainError, Result};
+use crate::models::Resource;
+use crate::repositories::{
+    base::Repository, resource::ResourceRepository, torrent_metadata::TorrentMetadataRepository,
+};
+use crate::types::bangumi::TorrentManifest;
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+// 下载或解析失败的种子最多尝试的次数
+const MAX_ATTEMPTS: i64 = 3;
+
+/// 下载资源的 .torrent 文件，保存精确大小与文件清单
+pub struct TorrentService {
+    pool: Arc<SqlitePool>,
+    fetcher: HttpFetcher,
+}
+
+impl TorrentService {
+    pub fn new(pool: Arc<SqlitePool>, config: &Config) -> Self {
+        Self {
+            pool,
+            fetcher: HttpFetcher::new(&config.http()),
+        }
+    }
+
+    /// 后台任务：处理一批尚未解析的种子，返回成功数
+    pub async fn fetch_pending(&self, batch: i64) -> Result<usize> {
+        let resources = TorrentMetadataRepository::new(&self.pool)
+            .pending_resources(MAX_ATTEMPTS, batch)
+            .await?;
+        let mut fetched = 0;
+        for resource in &resources {
+            if self.fetch_resource(resource).await.is_ok() {
+                fetched += 1;
+            }
+        }
+        if !resources.is_empty() {
+            tracing::info!("种子元数据：处理{}个，成功{}个", resources.len(), fetched);
+        }
+        Ok(fetched)
+    }
+
+    /// 获取资源的文件清单，尚未解析时立即下载种子（下载前选择文件）
+    pub async fn get_manifest(
+        &self,
+        resource_id: i64,
+        fetch: bool,
+    ) -> Result<Option<TorrentManifest>> {
+        let repo = TorrentMetadataRepository::new(&self.pool);
+        if fetch && repo.get_by_id(resource_id).await?.is_none() {
+            let resource = ResourceRepository::new(&self.pool)
+                .get_by_id(resource_id)
+                .await?
+                .ok_or_else(|| {
+                    AppError::Domain(DomainError::NotFound {
+                        resource_type: "resource".to_string(),
+                        resource_id,
+                    })
+                })?;
+            self.fetch_resource(&resource).await?;
+        }
+        let Some(metadata) = repo.get_by_id(resource_id).await? else {
+            return Ok(None);
+        };
+        let files = repo.list_files(resource_id).await?;
+        Ok(Some(TorrentManifest { metadata, files }))
+    }
+
+    async fn fetch_resource(&self, resource: &Resource) -> Result<()> {
+        let repo = TorrentMetadataRepository::new(&self.pool);
+        let resource_id = resource.id.unwrap_or_default();
+        let Some(torrent_url) = resource.torrent_url.as_deref().filter(|u| !u.is_empty()) else {
+            return Err(AppError::Domain(DomainError::Other(
+                "资源没有种子链接".to_string(),
+            )));
+        };
+        let result = self
+            .fetcher
+            .fetch_bytes(torrent_url)
+            .await
+            .and_then(|bytes| torrent_parser::parse_torrent(&bytes))
+            .and_then(|info| match &resource.magnet_hash {
+                // 种子与磁力链接不是同一个发布，文件清单不能用于该资源
+                Some(hash) if !hash.eq_ignore_ascii_case(&info.info_hash) => {
+                    Err(AppError::Domain(DomainError::Conflict(format!(
+                        "种子info hash {}与磁力链接{}不一致",
+                        info.info_hash, hash
+                    ))))
+                }
+                _ => Ok(info),
+            });
+        match result {
+            Ok(info) => repo.save_fetched(resource_id, &info).await,
+            Err(e) => {
+                tracing::warn!("获取资源{}的种子失败: {}", resource_id, e);
+                repo.save_failed(resource_id, &e.to_string()).await?;
+                Err(e)
+            }
+        }
+    }
+}
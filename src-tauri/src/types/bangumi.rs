@@ -103,15 +103,65 @@ pub struct EpisodeAvailabilityData {
 pub struct EpisodeResource {
     pub id: i64,
     pub episode_number: i64,
+    // 合集资源的结束集数
+    pub episode_end: Option<i64>,
+    // 发布版本，未标注时为空（视为 v1）
+    pub version: Option<i32>,
+    // 已被更高版本取代时为新版本的资源ID
+    pub superseded_by: Option<i64>,
+    pub episode_kind: crate::models::EpisodeKind,
+    // 类型内的精确集数，可为小数
+    pub episode_sort: Option<f64>,
     pub title: String,
     pub resolution: String,
     pub subtitle_type: String,
+    // 字幕语言代码：chs / cht / jpn / eng
+    pub subtitle_languages: Vec<String>,
+    pub subtitle_delivery: Option<crate::models::SubtitleDelivery>,
+    pub audio_language: Option<String>,
+    pub video_codec: Option<String>,
+    pub bit_depth: Option<i32>,
+    pub hdr: Option<String>,
+    pub audio_codec: Option<String>,
+    // 片源，如 BDRip / WebRip
+    pub release_source: Option<String>,
+    // 流媒体平台，如 Baha / CR / B-Global
+    pub platform: Option<String>,
+    pub container: Option<String>,
     pub magnet_url: String,
     pub torrent_url: String,
     pub release_date: String,
     pub size: String,
+    pub size_bytes: Option<i64>,
     pub group_id: i64,
     pub group_name: String,
+    pub source: String,
+    pub seeders: Option<i64>,
+    pub leechers: Option<i64>,
+}
+
+// get_anime_resources 的筛选条件
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ResourceQuery {
+    pub resolution: Option<String>,
+    pub subtitle_type: Option<String>,
+    // 需同时包含的字幕语言，如 ["chs"]
+    pub subtitle_languages: Vec<String>,
+    pub subtitle_delivery: Option<crate::models::SubtitleDelivery>,
+    pub audio_language: Option<String>,
+    pub video_codec: Option<String>,
+    pub bit_depth: Option<i32>,
+    pub hdr: Option<String>,
+    pub audio_codec: Option<String>,
+    // 片源，如 BDRip / WebRip
+    pub release_source: Option<String>,
+    pub platform: Option<String>,
+    pub container: Option<String>,
+    // 是否包含已被同组更高版本（v2/v3）取代的资源
+    pub include_superseded: bool,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -167,3 +217,38 @@ pub struct SearchLibraryResponse {
     pub bangumi_ids: Vec<i64>,
     pub pagination: Pagination,
 }
+
+// Bangumi 旧版搜索接口（/search/subject）的条目
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BangumiSearchItem {
+    pub id: i64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub name_cn: String,
+    #[serde(default)]
+    pub air_date: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BangumiSearchResponse {
+    #[serde(default)]
+    pub list: Option<Vec<BangumiSearchItem>>,
+}
+
+// 一次 bangumi_id 补全的结果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ReconcileSummary {
+    pub checked: i64,
+    pub auto_linked: i64,
+    // 生成了待确认候选的番剧数
+    pub proposed: i64,
+    pub no_candidates: i64,
+}
+
+// 资源的种子元数据与文件清单
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TorrentManifest {
+    pub metadata: crate::models::TorrentMetadata,
+    pub files: Vec<crate::models::TorrentFile>,
+}
@@ -12,6 +12,18 @@ pub enum CrawlerMode {
     Season,
     #[serde(rename = "year")]
     Year,
+    // 读取 Mikan RSS，增量抓取资源
+    #[serde(rename = "rss")]
+    Rss,
+    // 为每部已订阅番剧查询一次订阅源（如 Torznab 索引器）
+    #[serde(rename = "subscriptions")]
+    Subscriptions,
+    // 只抓取单部番剧的详情页，需提供mikan_id或bangumi_id
+    #[serde(rename = "bangumi")]
+    Bangumi,
+    // 用当前解析器重新解析已入库资源的标题，不访问资源站
+    #[serde(rename = "reprocess")]
+    Reprocess,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -43,6 +55,16 @@ pub struct CrawlerTaskCreate {
     pub year: Option<i64>,
     pub season: Option<SeasonName>,
     pub limit: Option<i64>,
+    // 资源来源名称，缺省为 mikan
+    pub source: Option<String>,
+    // RSS 模式参数：番剧RSS需提供mikan_id（可选subgroup_id），用户订阅RSS需提供rss_token
+    pub mikan_id: Option<i64>,
+    pub subgroup_id: Option<i64>,
+    pub rss_token: Option<String>,
+    // 按标题匹配的来源（如 dmhy）的搜索关键词，缺省时取mikan_id对应番剧的标题
+    pub keyword: Option<String>,
+    // Bangumi 模式参数，未提供mikan_id时按bangumi_id查找番剧
+    pub bangumi_id: Option<i64>,
 }
 
 impl Default for CrawlerTaskCreate {
@@ -52,6 +74,12 @@ impl Default for CrawlerTaskCreate {
             year: None,
             season: None,
             limit: None,
+            source: None,
+            mikan_id: None,
+            subgroup_id: None,
+            rss_token: None,
+            keyword: None,
+            bangumi_id: None,
         }
     }
 }
@@ -101,7 +129,7 @@ pub struct TaskResponse {
     pub task_type: CrawlerTaskType,
     pub status: CrawlerTaskStatus,
     pub parameters: Option<String>,
-    pub result_summary: Option<String>,
+    pub result_summary: Option<CrawlResultSummary>,
     pub created_at: Option<i64>,
     pub started_at: Option<i64>,
     pub completed_at: Option<i64>,
@@ -112,3 +140,137 @@ pub struct TaskResponse {
     pub processing_speed: Option<f64>,
     pub estimated_remaining: Option<f64>,
 }
+
+// 单次爬虫任务的结果摘要，以JSON保存在 crawler_task.result_summary，任务恢复后继续累加
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct CrawlResultSummary {
+    pub animes_added: Vec<SummaryAnime>,
+    pub animes_updated: Vec<SummaryAnime>,
+    pub new_subtitle_groups: Vec<SummaryGroup>,
+    // 新增资源按番剧与集数统计
+    pub new_resources: Vec<NewResourceCount>,
+    pub new_resource_total: i64,
+    // 只保留前 MAX_FAILED_PAGES 条失败记录，failed_page_total 为实际失败数
+    pub failed_pages: Vec<FailedPage>,
+    pub failed_page_total: i64,
+    // 无法处理的订阅源条目（如缺少资源页链接），同样只保留前 MAX_FAILED_PAGES 条
+    #[serde(default)]
+    pub failed_items: Vec<FailedItem>,
+    #[serde(default)]
+    pub failed_item_total: i64,
+    // 重新解析任务中字段有变化的资源数
+    #[serde(default)]
+    pub resources_reparsed: i64,
+    // 累计运行时长（毫秒），不含任务排队与中断的时间
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SummaryAnime {
+    pub mikan_id: i64,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SummaryGroup {
+    pub id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewResourceCount {
+    pub mikan_id: i64,
+    pub episode_number: Option<i32>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailedPage {
+    pub url: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FailedItem {
+    pub title: String,
+    pub reason: String,
+}
+
+impl CrawlResultSummary {
+    pub const MAX_FAILED_PAGES: usize = 100;
+
+    pub fn parse(json: Option<&str>) -> Option<Self> {
+        json.and_then(|s| serde_json::from_str(s).ok())
+    }
+
+    pub fn add_failed_page(&mut self, url: String, reason: String) {
+        self.failed_page_total += 1;
+        if self.failed_pages.len() < Self::MAX_FAILED_PAGES {
+            self.failed_pages.push(FailedPage { url, reason });
+        }
+    }
+
+    pub fn add_failed_item(&mut self, title: String, reason: String) {
+        self.failed_item_total += 1;
+        if self.failed_items.len() < Self::MAX_FAILED_PAGES {
+            self.failed_items.push(FailedItem { title, reason });
+        }
+    }
+
+    pub fn add_resource(&mut self, mikan_id: i64, episode_number: Option<i32>) {
+        self.new_resource_total += 1;
+        match self
+            .new_resources
+            .iter_mut()
+            .find(|r| r.mikan_id == mikan_id && r.episode_number == episode_number)
+        {
+            Some(entry) => entry.count += 1,
+            None => self.new_resources.push(NewResourceCount {
+                mikan_id,
+                episode_number,
+                count: 1,
+            }),
+        }
+    }
+}
+
+// 重新解析隔离页面的结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuarantineReparseResult {
+    pub id: i64,
+    // 解析结果不再可疑，隔离记录已删除
+    pub resolved: bool,
+    // 解析出的数据已写入数据库
+    pub ingested: bool,
+    // 仍然可疑时的原因
+    pub reason: Option<String>,
+    pub resource_count: i64,
+}
+
+// =============================================================================
+// Parser Evaluation Types
+// =============================================================================
+
+// 用当前解析器重新解析已入库标题，与已存储字段对比的结果
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ParserEvaluation {
+    pub total: i64,
+    pub fields: Vec<FieldDisagreement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct FieldDisagreement {
+    pub field: String,
+    pub disagreements: i64,
+    // 不一致数 / 资源总数
+    pub rate: f64,
+    pub samples: Vec<DisagreementSample>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DisagreementSample {
+    pub resource_id: i64,
+    pub title: String,
+    pub stored: Option<String>,
+    pub parsed: Option<String>,
+}
@@ -27,4 +27,7 @@ pub struct StartDownloadTask {
     pub name_cn: String,
     pub cover: String,
     pub total_size: i64,
+    // 只下载种子中的这些文件（torrent_file.file_index），为空时下载全部
+    #[serde(default)]
+    pub only_files: Option<Vec<usize>>,
 }
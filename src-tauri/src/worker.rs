@@ -1,10 +1,11 @@
 use crate::config::Config;
-use crate::error::{AppError, Result};
+use crate::error::{AppError, Result, TaskError};
 use crate::models::CrawlerTaskStatus;
 use crate::repositories::base::Repository;
 use crate::repositories::crawler_task::CrawlerTaskRepository;
 use crate::services::bangumi_service::BangumiService;
 use crate::services::crawler_service::CrawlerService;
+use crate::services::torrent_service::TorrentService;
 use futures_util::stream::StreamExt;
 use sqlx::SqlitePool;
 use std::collections::{HashMap, HashSet};
@@ -14,8 +15,6 @@ use tokio::sync::{Notify, RwLock, Semaphore};
 use tokio::time::{sleep, Duration};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
-// 新增：引入上海时区
-use chrono_tz::Asia::Shanghai;
 
 pub struct Worker {
     pool: Arc<SqlitePool>,
@@ -100,7 +99,8 @@ impl Worker {
                         continue;
                     };
 
-                    let mut crawler_service = CrawlerService::new(pool.clone(), task_id);
+                    let mut crawler_service =
+                        CrawlerService::new(pool.clone(), self.config.clone(), task_id);
                     let retry_count = self.retry_count;
                     let cancel_tokens = self.cancel_tokens.clone();
 
@@ -122,6 +122,11 @@ impl Worker {
                                     success = true;
                                     info!("Worker: 任务 {} 完成", task_id);
                                 }
+                                // 取消的任务保持Cancelled状态，不再重试，可通过resume_crawler_task恢复
+                                Err(AppError::Task(TaskError::Cancel(_))) => {
+                                    info!("Worker: 任务 {} 已取消", task_id);
+                                    break;
+                                }
                                 Err(e) => {
                                     warn!(
                                         "Worker: 任务 {} 失败 (尝试 {}/{}), 错误: {:?}",
@@ -183,30 +188,31 @@ async fn main_refresh_loop(pool: Arc<SqlitePool>, config: Config) {
     let mut last_sub = Utc::now().timestamp();
     let mut last_non_sub = Utc::now().timestamp();
     let mut last_calendar = Utc::now().timestamp();
+    let mut last_torrent_metadata = Utc::now().timestamp();
     let mut last_homepage_task_date = None;
+    let schedule_tz = config.schedule_timezone();
 
     loop {
-        let now_shanghai = Utc::now().with_timezone(&Shanghai);
-        let today = (
-            now_shanghai.year(),
-            now_shanghai.month(),
-            now_shanghai.day(),
-        );
+        let now_local = Utc::now().with_timezone(&schedule_tz);
+        let today = (now_local.year(), now_local.month(), now_local.day());
 
         if last_homepage_task_date != Some(today) {
-            let start_of_day = now_shanghai
+            let start_of_day = now_local
                 .date_naive()
                 .and_hms_opt(0, 0, 0)
                 .unwrap()
-                .and_local_timezone(Shanghai)
-                .unwrap()
+                .and_local_timezone(schedule_tz)
+                // 夏令时切换可能使零点不存在或重复
+                .earliest()
+                .unwrap_or(now_local)
                 .timestamp_millis();
-            let end_of_day = now_shanghai
+            let end_of_day = now_local
                 .date_naive()
                 .and_hms_opt(23, 59, 59)
                 .unwrap()
-                .and_local_timezone(Shanghai)
-                .unwrap()
+                .and_local_timezone(schedule_tz)
+                .latest()
+                .unwrap_or(now_local)
                 .timestamp_millis();
 
             let tasks = {
@@ -245,9 +251,7 @@ async fn main_refresh_loop(pool: Arc<SqlitePool>, config: Config) {
             if !has_completed {
                 let parameters = match serde_json::to_string(&CrawlerTaskCreate {
                     mode: CrawlerMode::Homepage,
-                    year: None,
-                    season: None,
-                    limit: None,
+                    ..Default::default()
                 }) {
                     Ok(p) => p,
                     Err(e) => {
@@ -262,7 +266,7 @@ async fn main_refresh_loop(pool: Arc<SqlitePool>, config: Config) {
                     task_type: CrawlerTaskType::Scheduled,
                     status: CrawlerTaskStatus::Pending,
                     result_summary: None,
-                    created_at: Some(now_shanghai.timestamp_millis()),
+                    created_at: Some(now_local.timestamp_millis()),
                     started_at: None,
                     completed_at: None,
                     error_message: None,
@@ -286,7 +290,7 @@ async fn main_refresh_loop(pool: Arc<SqlitePool>, config: Config) {
             last_homepage_task_date = Some(today);
         }
 
-        let now_ts = now_shanghai.timestamp();
+        let now_ts = now_local.timestamp();
         let sub_interval = config.bangumi_sub_refresh_interval.unwrap_or(3600);
         let nonsub_interval = config.bangumi_nonsub_refresh_interval.unwrap_or(43200);
         let calendar_interval = config.bangumi_calendar_refresh_interval.unwrap_or(86400);
@@ -310,6 +314,15 @@ async fn main_refresh_loop(pool: Arc<SqlitePool>, config: Config) {
             }
             last_calendar = now_ts;
         }
+        let torrent_interval = config.torrent_metadata_interval.unwrap_or(600);
+        if torrent_interval > 0 && now_ts - last_torrent_metadata >= torrent_interval {
+            let service = TorrentService::new(pool.clone(), &config);
+            let batch = config.torrent_metadata_batch.unwrap_or(20);
+            if let Err(e) = service.fetch_pending(batch).await {
+                error!("获取种子元数据失败: {:?}", e);
+            }
+            last_torrent_metadata = now_ts;
+        }
         sleep(Duration::from_secs(60)).await;
     }
 }
@@ -3,7 +3,7 @@
 // =============================================================================
 
 import { invoke } from '@tauri-apps/api/core';
-import type { BangumiCalendarItem, BangumiSubject, BangumiWeekday, EpisodeAvailabilityData, BangumiEpisodesData, EpisodeResourcesData, SearchLibraryResponse } from './bangumiTypes';
+import type { AnimeAlias, AnimeBangumiMapping, BangumiCalendarItem, TorrentManifest, BangumiMatchCandidate, ReconcileSummary, BangumiSubject, BangumiWeekday, EpisodeAvailabilityData, BangumiEpisodesData, EpisodeResourcesData, ResourceQuery, SearchLibraryResponse } from './bangumiTypes';
 import { debounceAsync, throttleAsync } from '../common/common'
 
 /**
@@ -45,10 +45,10 @@ export class BangumiApiService {
     }
 
     /**
-     * 获取集数可用性状态
+     * 获取集数可用性状态，episode_type 为 Bangumi 章节类型（缺省为本篇），键为该类型内的集数
      */
-    static async getEpisodeAvailability(bangumi_id: number): Promise<EpisodeAvailabilityData | null> {
-        const response: EpisodeAvailabilityData | null = await invoke('get_episode_availability', { bangumi_id });
+    static async getEpisodeAvailability(bangumi_id: number, episode_type?: number): Promise<EpisodeAvailabilityData | null> {
+        const response: EpisodeAvailabilityData | null = await invoke('get_episode_availability', { bangumi_id, episode_type });
         return response;
     }
 
@@ -68,28 +68,21 @@ export class BangumiApiService {
     }
 
     /**
-     * 获取特定集数的资源列表
+     * 获取特定集数的资源列表，episode 可为小数（如13.5），episode_type 同 Bangumi 章节类型。
+     * 默认不含已被同组更高版本（v2/v3）取代的资源
      */
-    static async getEpisodeResources(bangumi_id: number, episode: number): Promise<EpisodeResourcesData | null> {
-        const response: EpisodeResourcesData | null = await invoke('get_episode_resources', { bangumi_id, episode });
+    static async getEpisodeResources(bangumi_id: number, episode: number, episode_type?: number, include_superseded?: boolean): Promise<EpisodeResourcesData | null> {
+        const response: EpisodeResourcesData | null = await invoke('get_episode_resources', { bangumi_id, episode, episode_type, include_superseded });
         return response;
     }
 
     /**
-     * 获取番剧的所有资源列表
+     * 获取番剧的所有资源列表，默认不含已被同组更高版本（v2/v3）取代的资源
      */
-    static async getAnimeResources(bangumi_id: number, options?: {
-        resolution?: string,
-        subtitle_type?: string,
-        limit?: number,
-        offset?: number
-    }): Promise<EpisodeResourcesData> {
+    static async getAnimeResources(bangumi_id: number, options?: ResourceQuery): Promise<EpisodeResourcesData> {
         const response: EpisodeResourcesData | null = await invoke('get_anime_resources', {
             bangumi_id,
-            resolution: options?.resolution,
-            subtitle_type: options?.subtitle_type,
-            limit: options?.limit,
-            offset: options?.offset,
+            query: options,
         });
         return response!;
     }
@@ -135,6 +128,89 @@ export class BangumiApiService {
         }
         return { success, failed };
     }
+
+    /**
+     * 获取番剧别名
+     */
+    static async listAnimeAliases(mikan_id: number): Promise<AnimeAlias[]> {
+        const response: AnimeAlias[] = await invoke('list_anime_aliases', { mikan_id });
+        return response;
+    }
+
+    /**
+     * 添加番剧别名（如英文标题），用于匹配 Nyaa 等来源的资源
+     */
+    static async addAnimeAlias(mikan_id: number, alias: string): Promise<AnimeAlias> {
+        const response: AnimeAlias = await invoke('add_anime_alias', { mikan_id, alias });
+        return response;
+    }
+
+    /**
+     * 删除番剧别名
+     */
+    static async removeAnimeAlias(id: number): Promise<void> {
+        await invoke('remove_anime_alias', { id });
+    }
+
+    /**
+     * 获取资源的种子文件清单
+     * @param fetch 尚未解析时立即下载种子
+     */
+    static async getTorrentManifest(resource_id: number, fetch: boolean = false): Promise<TorrentManifest | null> {
+        return await invoke('get_torrent_manifest', { resource_id, fetch });
+    }
+
+    /**
+     * 获取 Mikan 番剧与 Bangumi 条目的映射，可按任一ID过滤
+     */
+    static async listAnimeMappings(mikan_id?: number, bangumi_id?: number): Promise<AnimeBangumiMapping[]> {
+        return await invoke('list_anime_mappings', { mikan_id, bangumi_id });
+    }
+
+    /**
+     * 添加映射，一个 Mikan 番剧可对应多个 Bangumi 条目（如分季）。
+     * 已存在时更新季度与集数偏移，分割放送的第二部分以第13集起算时 episode_offset 为12
+     */
+    static async addAnimeMapping(mikan_id: number, bangumi_id: number, season?: number, episode_offset?: number): Promise<AnimeBangumiMapping> {
+        return await invoke('add_anime_mapping', { mikan_id, bangumi_id, season, episode_offset });
+    }
+
+    /**
+     * 删除映射
+     */
+    static async removeAnimeMapping(id: number): Promise<void> {
+        await invoke('remove_anime_mapping', { id });
+    }
+
+    /**
+     * 为缺少 bangumi_id 的番剧搜索 Bangumi 条目，高置信度的自动关联
+     * @param limit 本次最多处理的番剧数
+     */
+    static async reconcileBangumiIds(limit?: number): Promise<ReconcileSummary> {
+        return await invoke('reconcile_bangumi_ids', { limit });
+    }
+
+    /**
+     * 获取待确认的 Bangumi 候选条目
+     */
+    static async listBangumiMatchCandidates(mikan_id?: number): Promise<BangumiMatchCandidate[]> {
+        return await invoke('list_bangumi_match_candidates', { mikan_id });
+    }
+
+    /**
+     * 确认候选条目，关联番剧
+     * 条目已关联其他番剧时会返回冲突错误，用户确认后传入 allow_shared 重新提交
+     */
+    static async confirmBangumiMatch(candidate_id: number, allow_shared = false): Promise<BangumiMatchCandidate> {
+        return await invoke('confirm_bangumi_match', { candidate_id, allow_shared });
+    }
+
+    /**
+     * 拒绝候选条目
+     */
+    static async rejectBangumiMatch(candidate_id: number): Promise<BangumiMatchCandidate> {
+        return await invoke('reject_bangumi_match', { candidate_id });
+    }
 }
 
 export default BangumiApiService;
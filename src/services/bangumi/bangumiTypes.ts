@@ -109,6 +109,28 @@ export interface BangumiEpisodesResponse extends ApiResponse<BangumiEpisode[]> {
 }
 
 // 资源相关类型定义
+// getAnimeResources 的筛选条件
+export interface ResourceQuery {
+    resolution?: string;
+    subtitle_type?: string;
+    // 需同时包含的字幕语言，如 ['chs']
+    subtitle_languages?: SubtitleLanguage[];
+    subtitle_delivery?: SubtitleDelivery;
+    audio_language?: string;
+    video_codec?: string;
+    bit_depth?: number;
+    hdr?: string;
+    audio_codec?: string;
+    // 片源，如 BDRip / WebRip
+    release_source?: string;
+    platform?: string;
+    container?: string;
+    // 是否包含已被同组更高版本（v2/v3）取代的资源
+    include_superseded?: boolean;
+    limit?: number;
+    offset?: number;
+}
+
 export interface SubtitleGroupResource {
     id: number;
     name: string;
@@ -116,18 +138,118 @@ export interface SubtitleGroupResource {
     resources: EpisodeResource[];
 }
 
+// 剧集类型，对应 Bangumi 章节类型：main=0 本篇、special=1 特别篇、op=2、ed=3；剧场版按本篇处理
+export type EpisodeKind = 'main' | 'special' | 'op' | 'ed' | 'movie';
+
+export type SubtitleLanguage = 'chs' | 'cht' | 'jpn' | 'eng';
+
+// 内封 / 内嵌 / 外挂
+export type SubtitleDelivery = 'soft' | 'hard' | 'external';
+
 export interface EpisodeResource {
     id: number;
     episode_number: number;
+    // 合集资源的结束集数
+    episode_end: number | null;
+    episode_kind: EpisodeKind;
+    // 发布版本，未标注时为空（视为 v1）
+    version: number | null;
+    // 已被更高版本取代时为新版本的资源ID
+    superseded_by: number | null;
+    // 类型内的精确集数，可为小数，如13.5
+    episode_sort: number | null;
     title: string;
     resolution: string;
     subtitle_type: string;
+    subtitle_languages: SubtitleLanguage[];
+    subtitle_delivery: SubtitleDelivery | null;
+    audio_language: string | null;
+    video_codec: string | null;
+    bit_depth: number | null;
+    hdr: string | null;
+    audio_codec: string | null;
+    // 片源，如 BDRip / WebRip
+    release_source: string | null;
+    // 流媒体平台，如 Baha / CR / B-Global
+    platform: string | null;
+    container: string | null;
     magnet_url: string;
     torrent_url: string;
     release_date: string;
     size: string;
+    size_bytes: number | null;
     group_id: number;
     group_name: string;
+    source: string;
+    // 资源健康度，仅部分来源（如 Nyaa）提供
+    seeders: number | null;
+    leechers: number | null;
+}
+
+export interface AnimeAlias {
+    id: number;
+    mikan_id: number;
+    alias: string;
+    created_at: number | null;
+}
+
+// 资源种子的元数据
+export interface TorrentMetadata {
+    resource_id: number;
+    status: 'fetched' | 'failed';
+    info_hash: string | null;
+    name: string | null;
+    total_size: number | null; // 精确字节数
+    piece_length: number | null;
+    file_count: number | null;
+    error_message: string | null;
+    attempts: number;
+    fetched_at: number;
+}
+
+export interface TorrentFile {
+    id: number;
+    resource_id: number;
+    file_index: number; // 下载时选择文件的序号
+    path: string;
+    size: number;
+}
+
+export interface TorrentManifest {
+    metadata: TorrentMetadata;
+    files: TorrentFile[];
+}
+
+// 用户维护的 Mikan 番剧与 Bangumi 条目映射，优先于 Mikan 页面上的链接
+export interface AnimeBangumiMapping {
+    id: number;
+    mikan_id: number;
+    bangumi_id: number;
+    created_at: number | null;
+    // 只匹配该季度的资源，为空时不限
+    season: number | null;
+    // Bangumi 集数 = 资源集数 - episode_offset
+    episode_offset: number;
+}
+
+// 缺少 bangumi_id 的番剧在 Bangumi 上的候选条目
+export interface BangumiMatchCandidate {
+    id: number;
+    mikan_id: number;
+    bangumi_id: number;
+    name: string;
+    name_cn: string | null;
+    air_date: string | null;
+    confidence: number; // 0~1
+    status: 'pending' | 'accepted' | 'rejected';
+    created_at: number;
+}
+
+export interface ReconcileSummary {
+    checked: number;
+    auto_linked: number;
+    proposed: number;
+    no_candidates: number;
 }
 
 export interface EpisodeResourcesData {
@@ -3,7 +3,14 @@
 // =============================================================================
 
 import { invoke } from '@tauri-apps/api/core';
-import type { CrawlerTaskCreate, TaskResponse } from './crawlerTypes';
+import type {
+    CrawlerTaskCreate,
+    ParseQuarantine,
+    ParserEvaluation,
+    QuarantineReparseResult,
+    SourceInfo,
+    TaskResponse
+} from './crawlerTypes';
 
 export class CrawlerApiService {
     /**
@@ -14,6 +21,14 @@ export class CrawlerApiService {
         return await invoke('create_crawler_task', { task: data });
     }
 
+    /**
+     * 只抓取单部番剧的最新资源
+     * @param bangumiId Bangumi 番剧ID
+     */
+    static async crawlAnime(bangumiId: number): Promise<TaskResponse> {
+        return await this.createTask({ mode: 'bangumi', bangumi_id: bangumiId });
+    }
+
     /**
      * 获取所有爬虫任务列表
      * @param page 页码，从1开始
@@ -43,6 +58,14 @@ export class CrawlerApiService {
         return await invoke('cancel_crawler_task', { task_id: taskId });
     }
 
+    /**
+     * 恢复失败或已取消的爬虫任务，从中断处继续
+     * @param taskId 任务ID
+     */
+    static async resumeTask(taskId: number): Promise<TaskResponse> {
+        return await invoke('resume_crawler_task', { task_id: taskId });
+    }
+
     /**
      * 删除特定爬虫任务
      * @param taskId 任务ID
@@ -59,6 +82,50 @@ export class CrawlerApiService {
         return await invoke('get_crawler_task_status', { task_id: taskId });
     }
 
+    /**
+     * 获取所有已注册的资源来源
+     */
+    static async listSources(): Promise<SourceInfo[]> {
+        return await invoke('list_resource_sources');
+    }
+
+    /**
+     * 获取被隔离的解析异常页面
+     * @param page 页码，从1开始
+     * @param pageSize 每页数量
+     */
+    static async listQuarantinedPages(page: number = 1, pageSize: number = 20):
+        Promise<ParseQuarantine[]> {
+        return await invoke('list_quarantined_pages', {
+            page,
+            page_size: pageSize
+        });
+    }
+
+    /**
+     * 用当前解析器重新解析隔离页面
+     * @param id 隔离记录ID
+     */
+    static async reparseQuarantinedPage(id: number): Promise<QuarantineReparseResult> {
+        return await invoke('reparse_quarantined_page', { id });
+    }
+
+    /**
+     * 丢弃隔离页面
+     * @param id 隔离记录ID
+     */
+    static async discardQuarantinedPage(id: number): Promise<void> {
+        return await invoke('discard_quarantined_page', { id });
+    }
+
+    /**
+     * 内部工具：用当前解析器重新解析全部资源标题，统计与已存储字段的不一致率
+     * @param sampleLimit 每个字段保留的不一致样例数
+     */
+    static async evaluateTextParser(sampleLimit: number = 20): Promise<ParserEvaluation> {
+        return await invoke('evaluate_text_parser', { sample_limit: sampleLimit });
+    }
+
     // 注意：WebSocket功能在Tauri中可以用事件系统替代
     // 如果需要实时更新，建议使用Tauri的事件监听机制
 }
@@ -3,10 +3,20 @@
 // =============================================================================
 
 export interface CrawlerTaskCreate {
-    mode: 'homepage' | 'season' | 'year';
+    mode: 'homepage' | 'season' | 'year' | 'rss' | 'subscriptions' | 'bangumi' | 'reprocess'; // reprocess：用当前解析器重新解析已入库资源
     year?: number;
     season?: '春' | '夏' | '秋' | '冬';
     limit?: number;
+    // 资源来源名称，缺省为 mikan
+    source?: string;
+    // RSS 模式参数
+    mikan_id?: number;
+    subgroup_id?: number;
+    rss_token?: string;
+    // 按标题匹配的来源（如 dmhy）的搜索关键词
+    keyword?: string;
+    // bangumi 模式参数，未提供 mikan_id 时按 bangumi_id 查找番剧
+    bangumi_id?: number;
 }
 
 export type CrawlerTaskType = 'manual' | 'schedule';
@@ -18,7 +28,7 @@ export interface TaskResponse {
     task_type: CrawlerTaskType;
     status: CrawlerTaskStatus;
     parameters?: string;
-    result_summary?: string;
+    result_summary?: CrawlResultSummary;
     created_at?: number; // Unix timestamp in milliseconds
     started_at?: number;
     completed_at?: number;
@@ -29,3 +39,93 @@ export interface TaskResponse {
     processing_speed?: number;
     estimated_remaining?: number;
 }
+
+// 爬虫任务结果摘要，任务恢复后继续累加
+export interface CrawlResultSummary {
+    animes_added: SummaryAnime[];
+    animes_updated: SummaryAnime[];
+    new_subtitle_groups: SummaryGroup[];
+    new_resources: NewResourceCount[];
+    new_resource_total: number;
+    failed_pages: FailedPage[]; // 最多保留100条
+    failed_page_total: number;
+    failed_items?: FailedItem[]; // 无法处理的订阅源条目，最多保留100条
+    failed_item_total?: number;
+    resources_reparsed?: number; // 重新解析任务中字段有变化的资源数
+    duration_ms: number;
+}
+
+export interface SummaryAnime {
+    mikan_id: number;
+    title: string;
+}
+
+export interface SummaryGroup {
+    id: number;
+    name: string;
+}
+
+export interface NewResourceCount {
+    mikan_id: number;
+    episode_number?: number;
+    count: number;
+}
+
+export interface FailedPage {
+    url: string;
+    reason: string;
+}
+
+export interface FailedItem {
+    title: string;
+    reason: string;
+}
+
+// 资源来源
+export interface SourceInfo {
+    name: string;
+    base_url: string;
+    enabled: boolean;
+}
+
+// 解析结果可疑而被隔离的页面
+export interface ParseQuarantine {
+    id: number;
+    task_id?: number;
+    source: string;
+    page_kind: 'list' | 'detail';
+    url: string;
+    mikan_id?: number;
+    reason: string;
+    raw_html: string;
+    created_at: number;
+}
+
+// 重新解析隔离页面的结果
+export interface QuarantineReparseResult {
+    id: number;
+    resolved: boolean; // 解析结果不再可疑，隔离记录已删除
+    ingested: boolean; // 解析出的数据已写入数据库
+    reason?: string;
+    resource_count: number;
+}
+
+// 解析器评估：已存储字段与当前解析器结果的差异
+export interface DisagreementSample {
+    resource_id: number;
+    title: string;
+    stored: string | null;
+    parsed: string | null;
+}
+
+export interface FieldDisagreement {
+    field: string;
+    disagreements: number;
+    rate: number; // 不一致数 / 资源总数
+    samples: DisagreementSample[];
+}
+
+export interface ParserEvaluation {
+    total: number;
+    fields: FieldDisagreement[];
+}
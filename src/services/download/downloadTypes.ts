@@ -14,6 +14,7 @@ export interface StartDownloadTask {
     name_cn: string
     cover: string
     total_size: number
+    only_files?: number[] // 只下载这些文件（TorrentFile.file_index）
 }
 
 // 下载事件结构体